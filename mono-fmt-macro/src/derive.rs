@@ -0,0 +1,446 @@
+use std::cell::Cell;
+
+use proc_macro2::{Ident, Span, TokenStream, TokenTree};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    parse_quote, spanned::Spanned, Attribute, Data, DeriveInput, Expr, Fields, Generics, Path,
+    Result, Token, Type, WherePredicate,
+};
+
+use crate::{
+    diagnostics,
+    format::{Count, FormatArgRef, FormatTrait, Piece},
+    format_str::FormatStr,
+    parse_args,
+    to_tokens::Scoped,
+    Input,
+};
+
+/// The path to `mono_fmt`, `::mono_fmt` unless set with `#[mono_fmt(crate = path)]`, for crates
+/// deriving through a re-export.
+fn crate_path(attrs: &[Attribute]) -> Result<TokenStream> {
+    let mut attrs = attrs.iter().filter(|attr| attr.path.is_ident("mono_fmt"));
+    let Some(attr) = attrs.next() else {
+        return Ok(quote! { ::mono_fmt });
+    };
+    if let Some(duplicate) = attrs.next() {
+        return Err(syn::Error::new(
+            duplicate.path.span(),
+            "duplicate `#[mono_fmt(...)]` attribute",
+        ));
+    }
+
+    attr.parse_args_with(|input: syn::parse::ParseStream<'_>| {
+        input.parse::<Token![crate]>()?;
+        input.parse::<Token![=]>()?;
+        Ok(input.parse::<Path>()?.into_token_stream())
+    })
+}
+
+/// Rejects `#[debug(...)]` where it does nothing, it only applies to fields.
+fn check_no_debug_attr(attrs: &[Attribute]) -> Result<()> {
+    match attrs.iter().find(|attr| attr.path.is_ident("debug")) {
+        Some(attr) => Err(syn::Error::new(
+            attr.path.span(),
+            "`#[debug(...)]` is only allowed on fields",
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Adds a `T: #bound` predicate for every type parameter, like the std derives do.
+fn add_trait_bounds(mut generics: Generics, bound: &TokenStream) -> Generics {
+    let params = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect::<Vec<_>>();
+
+    let where_clause = generics.make_where_clause();
+    for param in params {
+        where_clause
+            .predicates
+            .push(parse_quote! { #param: #bound });
+    }
+
+    generics
+}
+
+fn field_ident(idx: usize, name: Option<&Ident>) -> Ident {
+    match name {
+        Some(name) => format_ident!("__field_{}", name, span = Span::mixed_site()),
+        None => format_ident!("__field_{}", idx, span = Span::mixed_site()),
+    }
+}
+
+fn is_skipped(attrs: &[Attribute]) -> Result<bool> {
+    let mut skip = false;
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("debug")) {
+        let ident = attr.parse_args::<Ident>()?;
+        if ident == "skip" {
+            skip = true;
+        } else {
+            return Err(syn::Error::new(
+                ident.span(),
+                format!("unknown debug attribute `{ident}`, expected `skip`"),
+            ));
+        }
+    }
+    Ok(skip)
+}
+
+/// Generates the pattern binding all fields and the builder calls printing the non-skipped ones.
+fn fields_debug(
+    name: &str,
+    path: &TokenStream,
+    fields: &Fields,
+    fmt: &Ident,
+) -> Result<(TokenStream, TokenStream)> {
+    match fields {
+        Fields::Named(named) => {
+            let mut bindings = Vec::new();
+            let mut calls = Vec::new();
+            for (idx, field) in named.named.iter().enumerate() {
+                let field_name = field.ident.as_ref().unwrap();
+                let binding = field_ident(idx, Some(field_name));
+                bindings.push(quote! { #field_name: #binding });
+                if !is_skipped(&field.attrs)? {
+                    let field_str = field_name.to_string();
+                    let field_str = field_str.strip_prefix("r#").unwrap_or(&field_str);
+                    calls.push(quote! { .field(#field_str, #binding) });
+                }
+            }
+            Ok((
+                quote! { #path { #(#bindings),* } },
+                quote! { #fmt.debug_struct(#name) #(#calls)* .finish() },
+            ))
+        }
+        Fields::Unnamed(unnamed) => {
+            let mut bindings = Vec::new();
+            let mut calls = Vec::new();
+            for (idx, field) in unnamed.unnamed.iter().enumerate() {
+                let binding = field_ident(idx, None);
+                bindings.push(binding.clone());
+                if !is_skipped(&field.attrs)? {
+                    calls.push(quote! { .field(#binding) });
+                }
+            }
+            Ok((
+                quote! { #path ( #(#bindings),* ) },
+                quote! { #fmt.debug_tuple(#name) #(#calls)* .finish() },
+            ))
+        }
+        Fields::Unit => Ok((quote! { #path }, quote! { #fmt.write_str(#name) })),
+    }
+}
+
+pub fn debug(input: DeriveInput) -> Result<TokenStream> {
+    let krate = crate_path(&input.attrs)?;
+    check_no_debug_attr(&input.attrs)?;
+    let name = &input.ident;
+    let fmt = Ident::new("__f", Span::mixed_site());
+
+    let arms = match &input.data {
+        Data::Struct(data) => {
+            let (pat, body) =
+                fields_debug(&name.to_string(), &quote! { Self }, &data.fields, &fmt)?;
+            vec![quote! { #pat => #body }]
+        }
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|variant| {
+                check_no_debug_attr(&variant.attrs)?;
+                let variant_name = &variant.ident;
+                let (pat, body) = fields_debug(
+                    &variant_name.to_string(),
+                    &quote! { Self::#variant_name },
+                    &variant.fields,
+                    &fmt,
+                )?;
+                Ok(quote! { #pat => #body })
+            })
+            .collect::<Result<Vec<_>>>()?,
+        Data::Union(data) => {
+            return Err(syn::Error::new(
+                data.union_token.span(),
+                "`Debug` cannot be derived for unions",
+            ))
+        }
+    };
+
+    // `match self {}` is not exhaustive for a reference to an empty enum
+    let scrutinee = if arms.is_empty() {
+        quote! { *self }
+    } else {
+        quote! { self }
+    };
+
+    let generics = add_trait_bounds(input.generics.clone(), &quote! { #krate::Debug });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #krate::Debug for #name #ty_generics #where_clause {
+            fn fmt<__W: #krate::Write, __O: #krate::FmtOpts>(
+                &self,
+                #fmt: &mut #krate::Formatter<__W, __O>,
+            ) -> #krate::Result {
+                match #scrutinee {
+                    #(#arms,)*
+                }
+            }
+        }
+    })
+}
+
+fn trait_ident(format_trait: FormatTrait) -> Ident {
+    let name = match format_trait {
+        FormatTrait::Display => "Display",
+        FormatTrait::Debug => "Debug",
+        FormatTrait::Octal => "Octal",
+        FormatTrait::LowerHex => "LowerHex",
+        FormatTrait::UpperHex => "UpperHex",
+        FormatTrait::Pointer => "Pointer",
+        FormatTrait::Binary => "Binary",
+        FormatTrait::LowerExp => "LowerExp",
+        FormatTrait::UpperExp => "UpperExp",
+    };
+    Ident::new(name, Span::call_site())
+}
+
+fn mentions_param(tokens: TokenStream, params: &[Ident]) -> bool {
+    tokens.into_iter().any(|tt| match tt {
+        TokenTree::Ident(ident) => params.contains(&ident),
+        TokenTree::Group(group) => mentions_param(group.stream(), params),
+        _ => false,
+    })
+}
+
+/// Parses the `#[display("...", args)]` attribute into the same input `format_args!` takes.
+fn display_input(attrs: &[Attribute], krate: &TokenStream) -> Result<Option<Input>> {
+    let mut attrs = attrs.iter().filter(|attr| attr.path.is_ident("display"));
+    let Some(attr) = attrs.next() else {
+        return Ok(None);
+    };
+    if let Some(duplicate) = attrs.next() {
+        return Err(syn::Error::new(
+            duplicate.span(),
+            "duplicate `#[display(...)]` attribute",
+        ));
+    }
+
+    attr.parse_args_with(|input: syn::parse::ParseStream<'_>| {
+        let format_str = input.parse::<FormatStr>()?;
+        let (positional_args, named_args) = parse_args(input)?;
+        Ok(Some(Input {
+            prefix: quote! { #krate::_private },
+            format_str,
+            positional_args,
+            named_args,
+        }))
+    })
+}
+
+/// Generates the match arm for one struct or variant. Named fields are bound under their own
+/// names so the format string can capture them, tuple fields become the leading positional
+/// arguments.
+fn display_arm(
+    mut input: Input,
+    path: &TokenStream,
+    fields: &Fields,
+    fmt: &Ident,
+    krate: &TokenStream,
+    params: &[Ident],
+    bounds: &mut Vec<WherePredicate>,
+) -> Result<TokenStream> {
+    let str = input.format_str.value();
+    let fmt_parts = diagnostics::parse_format(&input.format_str, &str)?;
+
+    let pat = match fields {
+        Fields::Named(named) => {
+            let names = named.named.iter().map(|field| &field.ident);
+            quote! { #path { #(#names),* } }
+        }
+        Fields::Unnamed(unnamed) => {
+            let bindings = (0..unnamed.unnamed.len())
+                .map(|idx| field_ident(idx, None))
+                .collect::<Vec<_>>();
+            let exprs = bindings.iter().map(|binding| -> Expr {
+                parse_quote! { #binding }
+            });
+            input.positional_args.splice(0..0, exprs);
+            quote! { #path ( #(#bindings),* ) }
+        }
+        Fields::Unit => quote! { #path },
+    };
+    let tuple_fields = match fields {
+        Fields::Unnamed(unnamed) => unnamed.unnamed.len(),
+        Fields::Named(_) | Fields::Unit => 0,
+    };
+    diagnostics::check_args(
+        &input,
+        &str,
+        &fmt_parts,
+        diagnostics::Context::Derive {
+            fields: tuple_fields,
+        },
+    )?;
+
+    let mut position = 0;
+    for piece in &fmt_parts.pieces {
+        let Piece::Arg(arg) = piece else { continue };
+        if let Some(Count::Star) = arg.format_spec.formatter_args.precision {
+            position += 1;
+        }
+        let field = match arg.arg {
+            None => {
+                position += 1;
+                fields
+                    .iter()
+                    .nth(position - 1)
+                    .filter(|_| matches!(fields, Fields::Unnamed(_)))
+            }
+            Some(FormatArgRef::Positional(idx)) => fields
+                .iter()
+                .nth(idx)
+                .filter(|_| matches!(fields, Fields::Unnamed(_))),
+            Some(FormatArgRef::Named(name)) => fields.iter().find(|field| {
+                field.ident.as_ref().is_some_and(|ident| ident == name)
+                    && !input.named_args.iter().any(|(arg, _)| arg == name)
+            }),
+        };
+        if let Some(field) =
+            field.filter(|field| mentions_param(field.ty.to_token_stream(), params))
+        {
+            let ty: &Type = &field.ty;
+            let trait_ident = trait_ident(arg.format_spec.format_trait);
+            bounds.push(parse_quote! { #ty: #krate::#trait_ident });
+        }
+    }
+
+    let current_position = Cell::new(0);
+    let args = Scoped::new(&input, &fmt_parts, &current_position);
+
+    Ok(quote! { #pat => #krate::helpers::write(#fmt, #args) })
+}
+
+pub fn display(input: DeriveInput) -> Result<TokenStream> {
+    let krate = crate_path(&input.attrs)?;
+    let name = &input.ident;
+    let fmt = Ident::new("__f", Span::mixed_site());
+    let params = input
+        .generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect::<Vec<_>>();
+    let mut bounds = Vec::new();
+
+    let arms = match &input.data {
+        Data::Struct(data) => {
+            let Some(display) = display_input(&input.attrs, &krate)? else {
+                return Err(syn::Error::new(
+                    name.span(),
+                    "missing `#[display(\"...\")]` attribute",
+                ));
+            };
+            vec![display_arm(
+                display,
+                &quote! { Self },
+                &data.fields,
+                &fmt,
+                &krate,
+                &params,
+                &mut bounds,
+            )?]
+        }
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|variant| {
+                let variant_name = &variant.ident;
+                let path = quote! { Self::#variant_name };
+                match display_input(&variant.attrs, &krate)? {
+                    Some(display) => display_arm(
+                        display,
+                        &path,
+                        &variant.fields,
+                        &fmt,
+                        &krate,
+                        &params,
+                        &mut bounds,
+                    ),
+                    // unit variants print their name, like a fieldless `Debug`
+                    None if matches!(variant.fields, Fields::Unit) => {
+                        let variant_str = variant_name.to_string();
+                        Ok(quote! { #path => #fmt.write_str(#variant_str) })
+                    }
+                    None => Err(syn::Error::new(
+                        variant_name.span(),
+                        "missing `#[display(\"...\")]` attribute",
+                    )),
+                }
+            })
+            .collect::<Result<Vec<_>>>()?,
+        Data::Union(data) => {
+            return Err(syn::Error::new(
+                data.union_token.span(),
+                "`Display` cannot be derived for unions",
+            ))
+        }
+    };
+
+    let scrutinee = if arms.is_empty() {
+        quote! { *self }
+    } else {
+        quote! { self }
+    };
+
+    let mut generics = input.generics.clone();
+    generics.make_where_clause().predicates.extend(bounds);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #krate::Display for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn fmt<__W: #krate::Write, __O: #krate::FmtOpts>(
+                &self,
+                #fmt: &mut #krate::Formatter<__W, __O>,
+            ) -> #krate::Result {
+                match #scrutinee {
+                    #(#arms,)*
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    fn error(input: &str) -> String {
+        let input = syn::parse_str(input).unwrap();
+        super::debug(input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn debug_attr_outside_fields() {
+        assert_eq!(
+            error("#[debug(skip)] struct A(u8);"),
+            "`#[debug(...)]` is only allowed on fields"
+        );
+        assert_eq!(
+            error("enum A { #[debug(skip)] B }"),
+            "`#[debug(...)]` is only allowed on fields"
+        );
+    }
+
+    #[test]
+    fn crate_attr() {
+        assert_eq!(
+            error("#[mono_fmt(krate = x)] struct A;"),
+            "expected `crate`"
+        );
+        assert_eq!(
+            error("#[mono_fmt(crate = x)] #[mono_fmt(crate = y)] struct A;"),
+            "duplicate `#[mono_fmt(...)]` attribute"
+        );
+    }
+}
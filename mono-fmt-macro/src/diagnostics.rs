@@ -0,0 +1,463 @@
+//! Turning format string problems into `syn::Error`s, worded like rustc's.
+
+use std::ops::Range;
+
+use proc_macro2::Span;
+use syn::{spanned::Spanned, Error, Result};
+
+use crate::{
+    format::{Count, Format, FormatArg, FormatArgRef, Parse as _, Piece, ATTRIBUTES, COLORS},
+    format_str::FormatStr,
+    Input,
+};
+
+/// Maps a byte offset into the value of the literal to a byte offset into its source text,
+/// or `None` if the source can't be walked.
+fn source_offset(source: &str, value_offset: usize) -> Option<usize> {
+    if let Some(raw) = source.strip_prefix('r') {
+        let hashes = raw.find('"')?;
+        return Some(1 + hashes + 1 + value_offset);
+    }
+
+    let mut chars = source.char_indices().skip(1).peekable();
+    let mut value_pos = 0;
+    while value_pos < value_offset {
+        let (_, char) = chars.next()?;
+        if char != '\\' {
+            value_pos += char.len_utf8();
+            continue;
+        }
+        let (_, escape) = chars.next()?;
+        let unescaped = match escape {
+            'x' => {
+                chars.next()?;
+                chars.next()?;
+                Some('x')
+            }
+            'u' => {
+                let mut hex = String::new();
+                for (_, char) in chars.by_ref() {
+                    match char {
+                        '{' => {}
+                        '}' => break,
+                        _ => hex.push(char),
+                    }
+                }
+                char::from_u32(u32::from_str_radix(&hex.replace('_', ""), 16).ok()?)
+            }
+            '\n' => {
+                // a line continuation, skips the following whitespace and produces nothing
+                while chars.next_if(|(_, char)| char.is_whitespace()).is_some() {}
+                None
+            }
+            _ => Some(escape),
+        };
+        value_pos += unescaped.map_or(0, char::len_utf8);
+    }
+    chars.peek().map(|&(idx, _)| idx)
+}
+
+/// Points at `range` of the format string if the compiler allows it, or the whole literal.
+/// `Literal::subspan` only works on nightly, stable always gets the whole literal.
+pub fn format_str_span(format_str: &FormatStr, range: Range<usize>) -> Span {
+    let lit = &format_str.lit;
+    if !format_str.in_source {
+        return lit.span();
+    }
+    let token = lit.token();
+    let source = token.to_string();
+    source_offset(&source, range.start)
+        .zip(source_offset(&source, range.end))
+        .and_then(|(start, end)| token.subspan(start..end))
+        .unwrap_or_else(|| lit.span())
+}
+
+fn offset_in(str: &str, part: &str) -> usize {
+    part.as_ptr() as usize - str.as_ptr() as usize
+}
+
+/// Points at `part`, which borrows from `str`, the value of the format string.
+pub fn part_span(format_str: &FormatStr, str: &str, part: &str) -> Span {
+    let start = offset_in(str, part);
+    format_str_span(format_str, start..start + part.len())
+}
+
+/// Parses the format string, turning parser failures into errors pointing at the culprit.
+pub fn parse_format<'a>(lit: &FormatStr, str: &'a str) -> Result<Format<'a>> {
+    let rest = match Format::parse(str) {
+        Ok((_, format)) => return Ok(format),
+        Err(nom::Err::Error(err) | nom::Err::Failure(err)) => err.input,
+        Err(nom::Err::Incomplete(_)) => unreachable!("only complete parsers are used"),
+    };
+    let offset = offset_in(str, rest);
+    let error = |range: Range<usize>, message: String| {
+        Err(Error::new(format_str_span(lit, range), message))
+    };
+
+    let Some(found) = rest.chars().next() else {
+        return error(
+            offset..offset,
+            "invalid format string: expected `}` but string was terminated".to_owned(),
+        );
+    };
+
+    // an unknown item in a `(...)` style, after the format spec has started
+    let placeholder = str[..offset].rsplit('{').next().unwrap_or_default();
+    let in_spec = placeholder.contains(':');
+    let in_style = in_spec
+        && placeholder
+            .rsplit(':')
+            .next()
+            .is_some_and(|spec| spec.contains('(') && !spec.contains(')'));
+    if in_style {
+        let item = rest.trim_start_matches(|char: char| char == ',' || char.is_whitespace());
+        let item = &item[..item.find([',', ')', '}']).unwrap_or(item.len())].trim_end();
+        let start = offset_in(str, item);
+        let color = ["fg", "bg"]
+            .into_iter()
+            .find_map(|key| item.strip_prefix(key)?.trim_start().strip_prefix('='))
+            .map(str::trim_start);
+        return match color {
+            Some(color) if !color.is_empty() => {
+                let start = offset_in(str, color);
+                error(
+                    start..start + color.len(),
+                    format!(
+                        "unknown color `{color}`, expected one of {}",
+                        names(&COLORS)
+                    ),
+                )
+            }
+            _ if item.is_empty() => error(
+                offset..offset + found.len_utf8(),
+                format!("invalid style, expected `)`, found `{found}`"),
+            ),
+            _ => error(
+                start..start + item.len(),
+                format!(
+                    "unknown style `{item}`, expected `fg=<color>`, `bg=<color>` or one of {}",
+                    names(&ATTRIBUTES)
+                ),
+            ),
+        };
+    }
+
+    // placeholders accept any `}`, so the parser only stops at one outside of them
+    if found == '}' {
+        return error(
+            offset..offset + 1,
+            "invalid format string: unmatched `}` found".to_owned(),
+        );
+    }
+
+    // an identifier where the closing brace should be, after the format spec has started
+    let trait_len = rest
+        .find(|char: char| !unicode_ident::is_xid_continue(char))
+        .unwrap_or(rest.len());
+    if in_spec && trait_len > 0 && rest[trait_len..].starts_with('}') {
+        return error(
+            offset..offset + trait_len,
+            format!("unknown format trait `{}`", &rest[..trait_len]),
+        );
+    }
+
+    error(
+        offset..offset + found.len_utf8(),
+        format!("invalid format string: expected `}}`, found `{found}`"),
+    )
+}
+
+fn names(names: &[&str]) -> String {
+    let names = names
+        .iter()
+        .map(|name| format!("`{name}`"))
+        .collect::<Vec<_>>();
+    names.join(", ")
+}
+
+fn arguments(count: usize) -> String {
+    match count {
+        0 => "no arguments were given".to_owned(),
+        1 => "there is 1 argument".to_owned(),
+        _ => format!("there are {count} arguments"),
+    }
+}
+
+/// Where the arguments of a format string come from.
+#[derive(Clone, Copy)]
+pub enum Context {
+    /// `format_args!`, where every argument is written out.
+    FormatArgs,
+    /// A derive, which binds the fields as the first `fields` positional arguments (tuples)
+    /// or as captures (named fields). Neither have to be used.
+    Derive { fields: usize },
+}
+
+/// Checks that every positional reference is in range and that every argument is used.
+pub fn check_args(
+    input: &Input,
+    format_str: &str,
+    format: &Format<'_>,
+    context: Context,
+) -> Result<()> {
+    let positional_count = input.positional_args.len();
+    let mut used_positional = vec![false; positional_count];
+    let mut used_named = vec![false; input.named_args.len()];
+    let mut next_position = 0;
+    let mut implicit_count = 0;
+    let mut errors = Vec::new();
+
+    let span_of = |arg: &FormatArg<'_>| part_span(&input.format_str, format_str, arg.src);
+
+    let mut mark = |arg_ref: Option<FormatArgRef<'_>>, placeholder: &FormatArg<'_>| match arg_ref {
+        None => {
+            implicit_count = implicit_count.max(next_position + 1);
+            if let Some(used) = used_positional.get_mut(next_position) {
+                *used = true;
+            }
+            next_position += 1;
+        }
+        Some(FormatArgRef::Positional(idx)) => match used_positional.get_mut(idx) {
+            Some(used) => *used = true,
+            None => errors.push(Error::new(
+                span_of(placeholder),
+                format!(
+                    "invalid reference to positional argument {idx} ({})",
+                    arguments(positional_count)
+                ),
+            )),
+        },
+        Some(FormatArgRef::Named(name)) => {
+            if let Some(idx) = input.named_args.iter().position(|(arg, _)| arg == name) {
+                used_named[idx] = true;
+            } else if !input.format_str.in_source && matches!(context, Context::FormatArgs) {
+                errors.push(Error::new(
+                    span_of(placeholder),
+                    format!(
+                        "there is no argument named `{name}`, format strings built by \
+                         `concat!` or `stringify!` can't capture variables"
+                    ),
+                ));
+            }
+        }
+    };
+
+    for piece in &format.pieces {
+        let Piece::Arg(arg) = piece else { continue };
+        let args = &arg.format_spec.formatter_args;
+        // the order matches the codegen, `.*` takes its argument before the value
+        if let Some(Count::Star) = args.precision {
+            mark(None, arg);
+        }
+        mark(arg.arg, arg);
+        for count in [args.width, args.precision].into_iter().flatten() {
+            if let Count::Parameter(arg_ref) = count {
+                mark(Some(arg_ref), arg);
+            }
+        }
+    }
+
+    if implicit_count > positional_count {
+        let plural = if implicit_count == 1 { "" } else { "s" };
+        errors.push(Error::new(
+            input.format_str.span(),
+            format!(
+                "{implicit_count} positional argument{plural} in format string, but {}",
+                arguments(positional_count)
+            ),
+        ));
+    }
+
+    // like rustc, arguments only count as unused once every reference is valid
+    if errors.is_empty() {
+        let fields = match context {
+            Context::FormatArgs => 0,
+            Context::Derive { fields } => fields,
+        };
+        let unused = input
+            .positional_args
+            .iter()
+            .zip(&used_positional)
+            .skip(fields)
+            .map(|(expr, used)| (expr.span(), used, "argument never used"))
+            .chain(
+                input
+                    .named_args
+                    .iter()
+                    .zip(&used_named)
+                    .map(|((name, _), used)| (name.span(), used, "named argument never used")),
+            )
+            .filter(|(_, used, _)| !**used)
+            .map(|(span, _, message)| Error::new(span, message))
+            .collect::<Vec<_>>();
+        // like rustc, several unused arguments are summed up on the format string
+        if unused.len() > 1 {
+            errors.push(Error::new(
+                input.format_str.span(),
+                "multiple unused formatting arguments",
+            ));
+        }
+        errors.extend(unused);
+    }
+
+    errors
+        .into_iter()
+        .reduce(|mut all, error| {
+            all.combine(error);
+            all
+        })
+        .map_or(Ok(()), Err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Context;
+    use crate::Input;
+
+    fn errors(input: &str) -> Vec<String> {
+        errors_in(input, Context::FormatArgs)
+    }
+
+    fn errors_in(input: &str, context: Context) -> Vec<String> {
+        let result = syn::parse_str::<Input>(input).and_then(|input| {
+            let str = input.format_str.value();
+            let format = super::parse_format(&input.format_str, &str)?;
+            super::check_args(&input, &str, &format, context)
+        });
+        match result {
+            Ok(()) => Vec::new(),
+            Err(err) => err.into_iter().map(|err| err.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn valid() {
+        assert!(errors(r#"mono_fmt "{} {0} {a} {:1$} {:.*}", 1, 2, 3, 4, a = 5"#).is_empty());
+        assert!(errors(r#"mono_fmt "{{}}""#).is_empty());
+        assert!(errors(r#"mono_fmt "{0 } {0 :5 } {0:.} {0:-} {0:<} {0:}>2}", 1"#).is_empty());
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            errors(r#"mono_fmt "{:y}", 1"#),
+            ["unknown format trait `y`"]
+        );
+        assert_eq!(
+            errors(r#"mono_fmt "a {""#),
+            ["invalid format string: expected `}` but string was terminated"]
+        );
+        assert_eq!(
+            errors(r#"mono_fmt "a } b""#),
+            ["invalid format string: unmatched `}` found"]
+        );
+        assert_eq!(
+            errors(r#"mono_fmt "{:+-}", 1"#),
+            ["invalid format string: expected `}`, found `-`"]
+        );
+        assert_eq!(
+            errors(r#"mono_fmt "{:.*$}", 1, 2"#),
+            ["invalid format string: expected `}`, found `$`"]
+        );
+        assert_eq!(
+            errors(r#"mono_fmt "{ 0}", 1"#),
+            ["invalid format string: expected `}`, found `0`"]
+        );
+    }
+
+    #[test]
+    fn style_errors() {
+        assert!(
+            errors(r#"mono_fmt "{:(fg=red)} {:>4( bg = blue , bold)?} {:()}", 1, 2, 3"#).is_empty()
+        );
+        assert!(errors(r#"mono_fmt "{:(fg=purple)}", 1"#)[0].starts_with("unknown color `purple`"));
+        assert!(errors(r#"mono_fmt "{:(bold, loud)}", 1"#)[0].starts_with("unknown style `loud`"));
+        assert_eq!(
+            errors(r#"mono_fmt "{:(bold}", 1"#),
+            ["invalid style, expected `)`, found `}`"]
+        );
+    }
+
+    #[test]
+    fn argument_errors() {
+        // the arguments aren't reported as unused on top of the invalid reference
+        assert_eq!(
+            errors(r#"mono_fmt "{5}", 1, 2"#),
+            ["invalid reference to positional argument 5 (there are 2 arguments)"]
+        );
+        assert_eq!(
+            errors(r#"mono_fmt "{:1$}", 1"#),
+            ["invalid reference to positional argument 1 (there is 1 argument)"]
+        );
+        assert_eq!(errors(r#"mono_fmt "{}", 1, 2"#), ["argument never used"]);
+        assert_eq!(
+            errors(r#"mono_fmt "{} {}", 1"#),
+            ["2 positional arguments in format string, but there is 1 argument"]
+        );
+        assert_eq!(
+            errors(r#"mono_fmt "{}""#),
+            ["1 positional argument in format string, but no arguments were given"]
+        );
+        assert_eq!(
+            errors(r#"mono_fmt "{}", 1, 2, a = 3"#),
+            [
+                "multiple unused formatting arguments",
+                "argument never used",
+                "named argument never used",
+            ]
+        );
+        assert_eq!(
+            errors(r#"mono_fmt "", a = 1, 2"#),
+            ["positional arguments cannot follow named arguments"]
+        );
+        assert_eq!(
+            errors(r#"mono_fmt "{a}", a = 1, a = 2"#),
+            ["duplicate argument named `a`"]
+        );
+    }
+
+    #[test]
+    fn macro_built_format_str() {
+        assert!(errors(r#"mono_fmt concat!("{", "}", 1), 2"#).is_empty());
+        assert!(errors(r#"mono_fmt stringify!({}), 1"#).is_empty());
+        assert_eq!(
+            errors(r#"mono_fmt concat!("{a}")"#),
+            ["there is no argument named `a`, format strings built by `concat!` or `stringify!` \
+              can't capture variables"]
+        );
+        assert_eq!(
+            errors(r#"mono_fmt 1"#),
+            ["format argument must be a string literal"]
+        );
+        assert_eq!(
+            errors(r#"mono_fmt concat!(b"a")"#),
+            ["cannot concatenate a byte string literal"]
+        );
+        assert_eq!(
+            errors(r#"mono_fmt concat!("{}", b'a'), 1"#),
+            ["cannot concatenate a byte literal"]
+        );
+    }
+
+    #[test]
+    fn derive_context() {
+        let tuple = Context::Derive { fields: 2 };
+        assert!(errors_in(r#"mono_fmt "{1}", field_0, field_1"#, tuple).is_empty());
+        assert!(errors_in(r#"mono_fmt concat!("{name}")"#, tuple).is_empty());
+        assert_eq!(
+            errors_in(r#"mono_fmt "{2}", field_0, field_1"#, tuple),
+            ["invalid reference to positional argument 2 (there are 2 arguments)"]
+        );
+        assert_eq!(
+            errors_in(r#"mono_fmt "{}", field_0, field_1, 3"#, tuple),
+            ["argument never used"]
+        );
+    }
+
+    #[test]
+    fn source_offsets() {
+        assert_eq!(super::source_offset(r#""a{}""#, 1), Some(2));
+        assert_eq!(super::source_offset(r##"r#"a{}"#"##, 1), Some(4));
+        assert_eq!(super::source_offset(r#""\n\u{1F980}{}""#, 5), Some(12));
+    }
+}
@@ -2,14 +2,19 @@ use std::str::FromStr;
 
 use nom::{
     branch::alt,
-    bytes::complete::tag,
+    bytes::complete::{tag, take_while},
     character::complete::{anychar, char, digit1, none_of, satisfy},
-    combinator::{all_consuming, cut, map, map_res, opt, recognize, value},
-    multi::{many0, many1},
-    sequence::{delimited, pair, preceded, terminated},
+    combinator::{all_consuming, consumed, cut, map, map_opt, map_res, not, opt, recognize, value},
+    multi::{many0, many1, separated_list0},
+    sequence::{delimited, pair, preceded, separated_pair, terminated, tuple},
     IResult,
 };
 
+/// rustc skips whitespace after the argument and at the end of the format spec.
+fn whitespace(input: &str) -> IResult<&str, &str> {
+    take_while(char::is_whitespace)(input)
+}
+
 fn identifier(input: &str) -> IResult<&str, &str> {
     recognize(pair(
         alt((satisfy(unicode_ident::is_xid_start), char('_'))),
@@ -18,7 +23,7 @@ fn identifier(input: &str) -> IResult<&str, &str> {
 }
 
 pub trait Parse<'a>: Sized {
-    fn parse(input: &'a str) -> IResult<&str, Self>;
+    fn parse(input: &'a str) -> IResult<&'a str, Self>;
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -29,7 +34,7 @@ pub enum Align {
 }
 
 impl<'a> Parse<'a> for Align {
-    fn parse(input: &'a str) -> IResult<&str, Self> {
+    fn parse(input: &'a str) -> IResult<&'a str, Self> {
         alt((
             value(Self::Left, tag("<")),
             value(Self::Center, tag("^")),
@@ -45,7 +50,7 @@ pub enum Sign {
 }
 
 impl<'a> Parse<'a> for Sign {
-    fn parse(input: &'a str) -> IResult<&str, Self> {
+    fn parse(input: &'a str) -> IResult<&'a str, Self> {
         alt((value(Self::Plus, tag("+")), value(Self::Minus, tag("-"))))(input)
     }
 }
@@ -56,19 +61,133 @@ pub enum DebugHex {
     Upper,
 }
 
+/// The color names, the runtime `Color` variants in snake case.
+pub const COLORS: [&str; 17] = [
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "magenta",
+    "cyan",
+    "white",
+    "bright_black",
+    "bright_red",
+    "bright_green",
+    "bright_yellow",
+    "bright_blue",
+    "bright_magenta",
+    "bright_cyan",
+    "bright_white",
+    "default",
+];
+
+/// The attribute names, the runtime `Attributes` constants in lower case.
+pub const ATTRIBUTES: [&str; 8] = [
+    "bold",
+    "dim",
+    "italic",
+    "underline",
+    "blink",
+    "reverse",
+    "hidden",
+    "strikethrough",
+];
+
+#[derive(Debug, Clone, Copy)]
+pub struct Color(pub &'static str);
+
+impl<'a> Parse<'a> for Color {
+    fn parse(input: &'a str) -> IResult<&'a str, Self> {
+        map_opt(identifier, |name| {
+            COLORS
+                .iter()
+                .find(|color| **color == name)
+                .map(|color| Self(color))
+        })(input)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum StyleItem {
+    Foreground(Color),
+    Background(Color),
+    Attribute(&'static str),
+}
+
+impl<'a> Parse<'a> for StyleItem {
+    fn parse(input: &'a str) -> IResult<&'a str, Self> {
+        fn equals(input: &str) -> IResult<&str, char> {
+            delimited(whitespace, char('='), whitespace)(input)
+        }
+
+        alt((
+            map(
+                separated_pair(tag("fg"), equals, Color::parse),
+                |(_, color)| Self::Foreground(color),
+            ),
+            map(
+                separated_pair(tag("bg"), equals, Color::parse),
+                |(_, color)| Self::Background(color),
+            ),
+            map_opt(identifier, |name| {
+                ATTRIBUTES
+                    .iter()
+                    .find(|attribute| **attribute == name)
+                    .map(|attribute| Self::Attribute(attribute))
+            }),
+        ))(input)
+    }
+}
+
+/// `(fg=red, bold)`, the colors and attributes the value is written with.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Style {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    /// Bit `n` is set for `ATTRIBUTES[n]`
+    pub attributes: u8,
+}
+
+impl<'a> Parse<'a> for Style {
+    fn parse(input: &'a str) -> IResult<&'a str, Self> {
+        let items = separated_list0(tuple((whitespace, char(','), whitespace)), StyleItem::parse);
+        let (input, items) = preceded(
+            pair(tag("("), whitespace),
+            cut(terminated(items, pair(whitespace, tag(")")))),
+        )(input)?;
+
+        let mut style = Self::default();
+        for item in items {
+            match item {
+                StyleItem::Foreground(color) => style.foreground = Some(color),
+                StyleItem::Background(color) => style.background = Some(color),
+                StyleItem::Attribute(attribute) => {
+                    let bit = ATTRIBUTES.iter().position(|a| *a == attribute).unwrap();
+                    style.attributes |= 1 << bit;
+                }
+            }
+        }
+        Ok((input, style))
+    }
+}
+
 #[derive(Debug, Default, Clone, Copy)]
 pub struct FormatterArgs<'a> {
     pub align: Option<Align>,
+    pub fill: Option<char>,
     pub sign: Option<Sign>,
     pub alternate: bool,
     pub zero: bool,
     pub width: Option<Count<'a>>,
     pub precision: Option<Count<'a>>,
     pub debug_hex: Option<DebugHex>,
+    pub style: Option<Style>,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy)]
 pub enum FormatTrait {
+    #[default]
     Display,
     Debug,
     Octal,
@@ -80,20 +199,16 @@ pub enum FormatTrait {
     UpperExp,
 }
 
-impl Default for FormatTrait {
-    fn default() -> Self {
-        Self::Display
-    }
-}
-
 #[derive(Debug, Clone, Copy)]
 pub enum Count<'a> {
     Parameter(FormatArgRef<'a>),
     Integer(usize),
+    /// `.*`, takes the precision from the next positional argument
+    Star,
 }
 
 impl<'a> Parse<'a> for Count<'a> {
-    fn parse(input: &'a str) -> IResult<&str, Self> {
+    fn parse(input: &'a str) -> IResult<&'a str, Self> {
         alt((
             map(terminated(FormatArgRef::parse, tag("$")), Self::Parameter),
             map(map_res(digit1, usize::from_str), Self::Integer),
@@ -108,22 +223,27 @@ pub struct FormatSpec<'a> {
 }
 
 impl<'a> Parse<'a> for FormatSpec<'a> {
-    fn parse(input: &'a str) -> IResult<&str, Self> {
+    fn parse(input: &'a str) -> IResult<&'a str, Self> {
         let (input, align) = opt(alt((
             pair(anychar, Align::parse),
             map(Align::parse, |align| (' ', align)),
         )))(input)?;
-        let align = align.map(|(fill, align)| {
-            if fill != ' ' {
-                todo!()
-            }
-            align
-        });
+        let fill = align.and_then(|(fill, _)| (fill != ' ').then_some(fill));
+        let align = align.map(|(_, align)| align);
         let (input, sign) = opt(Sign::parse)(input)?;
         let (input, alternate) = opt(value(true, tag("#")))(input)?;
-        let (input, zero) = opt(value(true, tag("0")))(input)?;
+        // `0$` is a width taken from the first argument, not the zero flag
+        let (input, zero) = opt(value(true, terminated(tag("0"), not(tag("$")))))(input)?;
         let (input, width) = opt(Count::parse)(input)?;
-        let (input, precision) = opt(preceded(tag("."), Count::parse))(input)?;
+        // a `.` without a count is accepted and means no precision
+        let (input, precision) = map(
+            opt(preceded(
+                tag("."),
+                opt(alt((value(Count::Star, tag("*")), Count::parse))),
+            )),
+            Option::flatten,
+        )(input)?;
+        let (input, style) = opt(Style::parse)(input)?;
         let (input, debug_hex_and_format_trait) = opt(alt((
             value((None, FormatTrait::Debug), tag("?")),
             value((Some(DebugHex::Lower), FormatTrait::Debug), tag("x?")),
@@ -143,12 +263,14 @@ impl<'a> Parse<'a> for FormatSpec<'a> {
             FormatSpec {
                 formatter_args: FormatterArgs {
                     align,
+                    fill,
                     sign,
                     alternate: alternate.unwrap_or_default(),
                     zero: zero.unwrap_or_default(),
                     width,
                     precision,
                     debug_hex,
+                    style,
                 },
                 format_trait: format_trait.unwrap_or_default(),
             },
@@ -163,7 +285,7 @@ pub enum FormatArgRef<'a> {
 }
 
 impl<'a> Parse<'a> for FormatArgRef<'a> {
-    fn parse(input: &'a str) -> IResult<&str, Self> {
+    fn parse(input: &'a str) -> IResult<&'a str, Self> {
         alt((
             map(map_res(digit1, usize::from_str), FormatArgRef::Positional),
             map(identifier, FormatArgRef::Named),
@@ -175,17 +297,20 @@ impl<'a> Parse<'a> for FormatArgRef<'a> {
 pub struct FormatArg<'a> {
     pub arg: Option<FormatArgRef<'a>>,
     pub format_spec: FormatSpec<'a>,
+    /// The whole placeholder including the braces, used to point diagnostics at it
+    pub src: &'a str,
 }
 
 impl<'a> Parse<'a> for FormatArg<'a> {
-    fn parse(input: &'a str) -> IResult<&str, Self> {
-        let (input, arg) = opt(FormatArgRef::parse)(input)?;
-        let (input, format_spec) = opt(preceded(tag(":"), FormatSpec::parse))(input)?;
+    fn parse(input: &'a str) -> IResult<&'a str, Self> {
+        let (input, arg) = terminated(opt(FormatArgRef::parse), whitespace)(input)?;
+        let (input, format_spec) = opt(delimited(tag(":"), FormatSpec::parse, whitespace))(input)?;
         Ok((
             input,
             Self {
                 arg,
                 format_spec: format_spec.unwrap_or_default(),
+                src: "",
             },
         ))
     }
@@ -199,7 +324,7 @@ pub enum Piece<'a> {
 }
 
 impl<'a> Piece<'a> {
-    pub fn parse_lit(input: &'a str) -> IResult<&str, Self> {
+    pub fn parse_lit(input: &'a str) -> IResult<&'a str, Self> {
         alt((
             map(recognize(many1(none_of("{}"))), Self::Lit),
             value(Self::Lit("{"), tag("{{")),
@@ -207,16 +332,16 @@ impl<'a> Piece<'a> {
         ))(input)
     }
 
-    pub fn parse_arg(input: &'a str) -> IResult<&str, Self> {
+    pub fn parse_arg(input: &'a str) -> IResult<&'a str, Self> {
         map(
-            delimited(tag("{"), cut(FormatArg::parse), tag("}")),
-            Self::Arg,
+            consumed(delimited(tag("{"), cut(FormatArg::parse), cut(tag("}")))),
+            |(src, arg)| Self::Arg(FormatArg { src, ..arg }),
         )(input)
     }
 }
 
 impl<'a> Parse<'a> for Piece<'a> {
-    fn parse(input: &'a str) -> IResult<&str, Self> {
+    fn parse(input: &'a str) -> IResult<&'a str, Self> {
         alt((Self::parse_lit, Self::parse_arg))(input)
     }
 }
@@ -224,10 +349,15 @@ impl<'a> Parse<'a> for Piece<'a> {
 #[derive(Debug, Clone)]
 pub struct Format<'a> {
     pub pieces: Vec<Piece<'a>>,
+    /// The whole format string, the pieces borrow from it
+    pub src: &'a str,
 }
 
 impl<'a> Parse<'a> for Format<'a> {
-    fn parse(input: &'a str) -> IResult<&str, Self> {
-        all_consuming(map(many0(Piece::parse), |pieces| Self { pieces }))(input)
+    fn parse(input: &'a str) -> IResult<&'a str, Self> {
+        all_consuming(map(many0(Piece::parse), |pieces| Self {
+            pieces,
+            src: input,
+        }))(input)
     }
 }
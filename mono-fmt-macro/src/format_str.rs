@@ -0,0 +1,133 @@
+//! The format string, written as a literal or built by `concat!` and `stringify!`.
+
+use proc_macro2::Span;
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Error, Expr, ExprLit, ExprUnary, Lit, LitStr, Result, Token, UnOp,
+};
+
+pub struct FormatStr {
+    pub lit: LitStr,
+    /// Whether `lit` was written out in the source. Only then do its subspans point at the
+    /// placeholders and may it capture variables, like with std.
+    pub in_source: bool,
+}
+
+impl FormatStr {
+    pub fn value(&self) -> String {
+        self.lit.value()
+    }
+
+    pub fn span(&self) -> Span {
+        self.lit.span()
+    }
+}
+
+impl Parse for FormatStr {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let expr = input.parse::<Expr>()?;
+        match unwrap_groups(&expr) {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(lit), ..
+            }) => Ok(Self {
+                lit: lit.clone(),
+                in_source: true,
+            }),
+            Expr::Macro(mac)
+                if is_builtin(&mac.mac.path, "concat")
+                    || is_builtin(&mac.mac.path, "stringify") =>
+            {
+                let mut value = String::new();
+                expand(&expr, &mut value)?;
+                Ok(Self {
+                    lit: LitStr::new(&value, expr.span()),
+                    in_source: false,
+                })
+            }
+            _ => Err(Error::new(
+                expr.span(),
+                "format argument must be a string literal",
+            )),
+        }
+    }
+}
+
+/// Looks through the invisible groups `macro_rules!` wraps around `$fmt:expr` fragments.
+fn unwrap_groups(mut expr: &Expr) -> &Expr {
+    while let Expr::Group(group) = expr {
+        expr = &group.expr;
+    }
+    expr
+}
+
+/// Whether `path` is `name`, `std::name` or `core::name`.
+fn is_builtin(path: &syn::Path, name: &str) -> bool {
+    let mut segments = path.segments.iter().rev();
+    segments.next().is_some_and(|last| last.ident == name)
+        && match (segments.next(), segments.next()) {
+            (None, _) => true,
+            (Some(krate), None) => krate.ident == "std" || krate.ident == "core",
+            (Some(_), Some(_)) => false,
+        }
+}
+
+/// Appends what `expr` evaluates to as an argument of `concat!`.
+fn expand(expr: &Expr, out: &mut String) -> Result<()> {
+    match unwrap_groups(expr) {
+        Expr::Lit(ExprLit { lit, .. }) => match lit {
+            Lit::Str(lit) => out.push_str(&lit.value()),
+            Lit::Char(lit) => out.push(lit.value()),
+            Lit::Int(lit) => out.push_str(lit.base10_digits()),
+            Lit::Float(lit) => out.push_str(lit.base10_digits()),
+            Lit::Bool(lit) => out.push_str(if lit.value { "true" } else { "false" }),
+            Lit::Byte(_) => {
+                return Err(Error::new(lit.span(), "cannot concatenate a byte literal"))
+            }
+            Lit::ByteStr(_) => {
+                return Err(Error::new(
+                    lit.span(),
+                    "cannot concatenate a byte string literal",
+                ))
+            }
+            Lit::Verbatim(_) => {
+                return Err(Error::new(lit.span(), "cannot concatenate this literal"))
+            }
+        },
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr: operand,
+            ..
+        }) if matches!(
+            unwrap_groups(operand),
+            Expr::Lit(ExprLit {
+                lit: Lit::Int(_) | Lit::Float(_),
+                ..
+            })
+        ) =>
+        {
+            out.push('-');
+            expand(operand, out)?;
+        }
+        Expr::Macro(mac) if is_builtin(&mac.mac.path, "concat") => {
+            let args = mac
+                .mac
+                .parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)?;
+            for arg in &args {
+                expand(arg, out)?;
+            }
+        }
+        // within a proc macro this is rustc's own printing, the same as for `stringify!`
+        Expr::Macro(mac) if is_builtin(&mac.mac.path, "stringify") => {
+            out.push_str(&mac.mac.tokens.to_string());
+        }
+        expr => {
+            return Err(Error::new(
+                expr.span(),
+                "expected a literal, `concat!` or `stringify!`",
+            ))
+        }
+    }
+    Ok(())
+}
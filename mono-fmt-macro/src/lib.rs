@@ -3,21 +3,26 @@
 
 use std::cell::Cell;
 
-use format::Parse as _;
+use format_str::FormatStr;
 use proc_macro::TokenStream;
 use quote::{quote, ToTokens};
 use syn::{
     parse::{Parse, ParseStream},
-    parse_macro_input, Expr, ExprAssign, ExprPath, Ident, LitStr, PathArguments, Result, Token,
+    parse_macro_input,
+    spanned::Spanned,
+    DeriveInput, Expr, ExprAssign, ExprPath, Ident, PathArguments, Result, Token,
 };
 use to_tokens::Scoped;
 
+mod derive;
+mod diagnostics;
 mod format;
+mod format_str;
 mod to_tokens;
 
 struct Input {
     prefix: proc_macro2::TokenStream,
-    format_str: LitStr,
+    format_str: FormatStr,
     positional_args: Vec<Expr>,
     named_args: Vec<(Ident, Expr)>,
 }
@@ -27,41 +32,9 @@ impl Parse for Input {
         let crate_ident = input.parse::<syn::Path>()?;
         let prefix = quote! { #crate_ident::_private };
 
-        let format_str = input.parse::<LitStr>()?;
+        let format_str = input.parse::<FormatStr>()?;
+        let (positional_args, named_args) = parse_args(input)?;
 
-        let mut positional_args = Vec::new();
-        let mut named_args = Vec::new();
-        let mut onto_named = false;
-        while input.peek(Token![,]) {
-            input.parse::<Token![,]>()?;
-            if input.is_empty() {
-                break;
-            }
-            let expr = input.parse::<Expr>()?;
-            match expr {
-                Expr::Assign(ExprAssign { left, right, .. })
-                    if matches!(
-                        &*left,
-                        Expr::Path(ExprPath { path, .. })
-                            if path.segments.len() == 1 && matches!(path.segments[0].arguments, PathArguments::None)
-                    ) =>
-                {
-                    let ident = if let Expr::Path(ExprPath { mut path, .. }) = *left {
-                        path.segments.pop().unwrap().into_value().ident
-                    } else {
-                        panic!()
-                    };
-                    named_args.push((ident, *right));
-                    onto_named = true;
-                }
-                expr => {
-                    if onto_named {
-                        panic!("positional arg after named")
-                    }
-                    positional_args.push(expr);
-                }
-            }
-        }
         Ok(Self {
             prefix,
             format_str,
@@ -71,23 +44,95 @@ impl Parse for Input {
     }
 }
 
-fn format_args_impl(input: Input) -> syn::Result<TokenStream> {
+type Args = (Vec<Expr>, Vec<(Ident, Expr)>);
+
+/// Parses the `, arg, name = arg` list following the format string.
+fn parse_args(input: ParseStream<'_>) -> Result<Args> {
+    let mut positional_args = Vec::new();
+    let mut named_args = Vec::new();
+    let mut onto_named = false;
+    while input.peek(Token![,]) {
+        input.parse::<Token![,]>()?;
+        if input.is_empty() {
+            break;
+        }
+        let expr = input.parse::<Expr>()?;
+        match expr {
+            Expr::Assign(ExprAssign { left, right, .. })
+                if matches!(
+                    &*left,
+                    Expr::Path(ExprPath { path, .. })
+                        if path.segments.len() == 1 && matches!(path.segments[0].arguments, PathArguments::None)
+                ) =>
+            {
+                let ident = if let Expr::Path(ExprPath { mut path, .. }) = *left {
+                    path.segments.pop().unwrap().into_value().ident
+                } else {
+                    unreachable!("checked by the match guard")
+                };
+                if named_args.iter().any(|(name, _)| *name == ident) {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("duplicate argument named `{ident}`"),
+                    ));
+                }
+                named_args.push((ident, *right));
+                onto_named = true;
+            }
+            expr => {
+                if onto_named {
+                    return Err(syn::Error::new(
+                        expr.span(),
+                        "positional arguments cannot follow named arguments",
+                    ));
+                }
+                positional_args.push(expr);
+            }
+        }
+    }
+    Ok((positional_args, named_args))
+}
+
+fn format_args_impl(input: &Input) -> syn::Result<proc_macro2::TokenStream> {
     let str = input.format_str.value();
-    let (_, fmt_parts) = format::Format::parse(&str).unwrap();
+    let fmt_parts = diagnostics::parse_format(&input.format_str, &str)?;
+    diagnostics::check_args(input, &str, &fmt_parts, diagnostics::Context::FormatArgs)?;
 
     let current_position = Cell::new(0);
 
-    Ok(Scoped::new(&input, &fmt_parts, &current_position)
-        .to_token_stream()
-        .into())
+    Ok(Scoped::new(input, &fmt_parts, &current_position).to_token_stream())
 }
 
 #[proc_macro]
 pub fn __format_args(tokens: TokenStream) -> TokenStream {
     let input = parse_macro_input!(tokens as Input);
 
-    match format_args_impl(input) {
-        Ok(tt) => tt,
+    match format_args_impl(&input) {
+        Ok(tt) => tt.into(),
+        Err(err) => {
+            // a block, as an expression can only hold a single `compile_error!` invocation
+            let errors = err.to_compile_error();
+            quote! { { #errors } }.into()
+        }
+    }
+}
+
+#[proc_macro_derive(Debug, attributes(debug, mono_fmt))]
+pub fn derive_debug(tokens: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(tokens as DeriveInput);
+
+    match derive::debug(input) {
+        Ok(tt) => tt.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+#[proc_macro_derive(Display, attributes(display, mono_fmt))]
+pub fn derive_display(tokens: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(tokens as DeriveInput);
+
+    match derive::display(input) {
+        Ok(tt) => tt.into(),
         Err(err) => err.to_compile_error().into(),
     }
 }
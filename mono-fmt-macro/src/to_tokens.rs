@@ -4,9 +4,10 @@ use proc_macro2::{Ident, Span, TokenStream};
 use quote::{quote, ToTokens};
 
 use crate::{
+    diagnostics,
     format::{
-        Align, Count, DebugHex, Format, FormatArg, FormatArgRef, FormatTrait, FormatterArgs, Piece,
-        Sign,
+        Align, Color, Count, DebugHex, Format, FormatArg, FormatArgRef, FormatTrait, FormatterArgs,
+        Piece, Sign, Style, ATTRIBUTES,
     },
     Input,
 };
@@ -48,6 +49,54 @@ impl<'a, T> Scoped<'a, T> {
     fn as_ref(&self) -> &'a T {
         self.inner
     }
+
+    /// Resolves an argument reference to the binding holding a reference to its value.
+    /// `None` takes the next positional argument.
+    fn arg_tokens(&self, arg: Option<FormatArgRef<'_>>) -> TokenStream {
+        match arg {
+            None => {
+                let current_position = self.current_position.get();
+                self.current_position.set(current_position + 1);
+
+                pos_arg_ident(current_position).to_token_stream()
+            }
+            Some(FormatArgRef::Positional(idx)) => pos_arg_ident(idx).to_token_stream(),
+            // captures are bound next to the named arguments, see `captures`
+            Some(FormatArgRef::Named(name)) => named_arg_ident(name).to_token_stream(),
+        }
+    }
+}
+
+impl<'a> Scoped<'a, Format<'a>> {
+    /// The names referenced without a named argument, captured from the surrounding scope.
+    /// Every name appears once, no matter how often or where it is used, so that it is only
+    /// evaluated once, like with std.
+    fn captures(&self) -> Vec<&'a str> {
+        let mut captures = Vec::new();
+        for piece in &self.inner.pieces {
+            let Piece::Arg(arg) = piece else { continue };
+            let args = &arg.format_spec.formatter_args;
+            let counts = [args.width, args.precision].into_iter().flatten();
+            let refs = arg
+                .arg
+                .into_iter()
+                .chain(counts.filter_map(|count| match count {
+                    Count::Parameter(arg) => Some(arg),
+                    Count::Integer(_) | Count::Star => None,
+                }));
+            for arg in refs {
+                let FormatArgRef::Named(name) = arg else {
+                    continue;
+                };
+                if !captures.contains(&name)
+                    && !self.input.named_args.iter().any(|(arg, _)| arg == name)
+                {
+                    captures.push(name);
+                }
+            }
+        }
+        captures
+    }
 }
 
 impl ToTokens for Scoped<'_, Format<'_>> {
@@ -56,10 +105,20 @@ impl ToTokens for Scoped<'_, Format<'_>> {
 
         let input = &self.input;
 
-        let pos_args = input.positional_args.iter();
-        let named_args = input.named_args.iter().map(|(_, expr)| expr);
+        let captures = self.captures();
+
+        let pos_args = input.positional_args.iter().map(ToTokens::to_token_stream);
+        let named_args = input
+            .named_args
+            .iter()
+            .map(|(_, expr)| expr.to_token_stream());
+        // the span of the name in the format string, so an unknown name is reported there
+        let captured_args = captures.iter().map(|name| {
+            let span = diagnostics::part_span(&input.format_str, self.inner.src, name);
+            Ident::new(name, span).to_token_stream()
+        });
 
-        let args = pos_args.chain(named_args);
+        let args = pos_args.chain(named_args).chain(captured_args);
 
         let pos_idents = input
             .positional_args
@@ -70,7 +129,8 @@ impl ToTokens for Scoped<'_, Format<'_>> {
         let named_idents = input
             .named_args
             .iter()
-            .map(|(name, _)| named_arg_ident(name));
+            .map(|(name, _)| named_arg_ident(name))
+            .chain(captures.iter().map(named_arg_ident));
 
         let idents = pos_idents.chain(named_idents);
 
@@ -105,33 +165,31 @@ impl ToTokens for Scoped<'_, FormatArg<'_>> {
 
         let base = self.inner.format_spec.format_trait;
 
-        let expr = match self.inner.arg {
-            None => {
-                let current_position = self.current_position.get();
-                self.current_position.set(current_position + 1);
-
-                pos_arg_ident(current_position).to_token_stream()
-            }
-            Some(FormatArgRef::Positional(idx)) => pos_arg_ident(idx).to_token_stream(),
-            Some(FormatArgRef::Named(name)) => self
-                .input
-                .named_args
-                .iter()
-                .find(|(arg, _)| arg == name)
-                .map(|(name, _)| named_arg_ident(name).to_token_stream())
-                .unwrap_or_else(|| {
-                    let ident = Ident::new(name, self.input.format_str.span());
-                    quote! { &#ident }
-                }),
-        };
-
-        let opt_ty = opt_ty_tokens(self.scope(&self.inner.format_spec.formatter_args));
+        // `.*` takes its argument before the value, so the options have to be resolved first
         let opt_values = opt_value_tokens(self.scope(&self.inner.format_spec.formatter_args));
+        let opt_ty = opt_ty_tokens(self.scope(&self.inner.format_spec.formatter_args));
+
+        let expr = self.arg_tokens(self.inner.arg);
 
         tokens.extend(quote! { #prefix::#base::<_, #opt_ty>(#expr, #opt_values) })
     }
 }
 
+/// The runtime value of a width or precision, `None` if it is known at compile time.
+fn count_value_tokens(
+    scope: &Scoped<'_, FormatterArgs<'_>>,
+    count: Count<'_>,
+) -> Option<TokenStream> {
+    let arg = match count {
+        Count::Integer(_) => return None,
+        Count::Parameter(arg) => scope.arg_tokens(Some(arg)),
+        Count::Star => scope.arg_tokens(None),
+    };
+    // the binding may be a reference to a reference (like fields in derives), so let deref
+    // coercion get to the `usize`
+    Some(quote! { *{ let count: &usize = #arg; count } })
+}
+
 fn opt_value_tokens(scope: Scoped<'_, FormatterArgs<'_>>) -> TokenStream {
     let args = &scope.inner;
     let prefix = &scope.input.prefix;
@@ -143,15 +201,18 @@ fn opt_value_tokens(scope: Scoped<'_, FormatterArgs<'_>>) -> TokenStream {
     }
 
     if let Some(width) = args.width {
-        let width = match width {
-            Count::Integer(int) => int,
-            Count::Parameter(_) => panic!("parameter counts are not supported right now"),
+        opts = match count_value_tokens(&scope, width) {
+            Some(width) => quote! { #prefix::WithRuntimeWidth(#opts, #width) },
+            None => quote! { #prefix::WithWidth(#opts) },
         };
-        opts = quote! { #prefix::WithWidth(#opts) };
     }
 
     if let Some(align) = args.align {
-        opts = quote! { #prefix::WithAlign(#opts) };
+        opts = quote! { #prefix::#align(#opts) };
+    }
+
+    if args.fill.is_some() {
+        opts = quote! { #prefix::WithFill(#opts) };
     }
 
     if let Some(Sign::Plus) = args.sign {
@@ -163,11 +224,10 @@ fn opt_value_tokens(scope: Scoped<'_, FormatterArgs<'_>>) -> TokenStream {
     }
 
     if let Some(precision) = args.precision {
-        let precision = match precision {
-            Count::Integer(int) => int,
-            Count::Parameter(_) => panic!("parameter counts are not supported right now"),
+        opts = match count_value_tokens(&scope, precision) {
+            Some(precision) => quote! { #prefix::WithRuntimePrecision(#opts, #precision) },
+            None => quote! { #prefix::WithPrecision(#opts) },
         };
-        opts = quote! { #prefix::WithPrecision(#opts) };
     }
 
     if let Some(Sign::Plus) = args.sign {
@@ -186,9 +246,47 @@ fn opt_value_tokens(scope: Scoped<'_, FormatterArgs<'_>>) -> TokenStream {
         opts = quote! { #prefix::WithDebugUpperHex(#opts) };
     }
 
+    if let Some(style) = args.style {
+        let style = style_tokens(&scope, style);
+        opts = quote! { #prefix::WithStyle(#opts, #style) };
+    }
+
     opts
 }
 
+/// Builds the runtime `Style`.
+fn style_tokens(scope: &Scoped<'_, FormatterArgs<'_>>, style: Style) -> TokenStream {
+    let prefix = &scope.input.prefix;
+
+    let color = |color: Option<Color>| match color {
+        Some(Color(name)) => {
+            let variant = name
+                .split('_')
+                .map(|word| word[..1].to_uppercase() + &word[1..])
+                .collect::<String>();
+            let variant = Ident::new(&variant, Span::call_site());
+            quote! { ::core::option::Option::Some(#prefix::Color::#variant) }
+        }
+        None => quote! { ::core::option::Option::None },
+    };
+    let foreground = color(style.foreground);
+    let background = color(style.background);
+
+    let attributes = ATTRIBUTES
+        .iter()
+        .enumerate()
+        .filter(|(bit, _)| style.attributes & (1 << bit) != 0)
+        .map(|(_, name)| Ident::new(&name.to_uppercase(), Span::call_site()));
+
+    quote! {
+        #prefix::Style {
+            foreground: #foreground,
+            background: #background,
+            attributes: #prefix::Attributes::NONE #(.union(#prefix::Attributes::#attributes))*,
+        }
+    }
+}
+
 fn opt_ty_tokens(scope: Scoped<'_, FormatterArgs<'_>>) -> TokenStream {
     let args = &scope.inner;
     let prefix = &scope.input.prefix;
@@ -200,15 +298,18 @@ fn opt_ty_tokens(scope: Scoped<'_, FormatterArgs<'_>>) -> TokenStream {
     }
 
     if let Some(width) = args.width {
-        let width = match width {
-            Count::Integer(int) => int,
-            Count::Parameter(_) => panic!("parameter counts are not supported right now"),
+        opts = match width {
+            Count::Integer(width) => quote! { #prefix::WithWidth<#opts, #width> },
+            Count::Parameter(_) | Count::Star => quote! { #prefix::WithRuntimeWidth<#opts> },
         };
-        opts = quote! { #prefix::WithWidth<#opts, #width> };
     }
 
     if let Some(align) = args.align {
-        opts = quote! { #prefix::WithAlign<#opts, #align> };
+        opts = quote! { #prefix::#align<#opts> };
+    }
+
+    if let Some(fill) = args.fill {
+        opts = quote! { #prefix::WithFill<#opts, #fill> };
     }
 
     if let Some(Sign::Plus) = args.sign {
@@ -220,11 +321,12 @@ fn opt_ty_tokens(scope: Scoped<'_, FormatterArgs<'_>>) -> TokenStream {
     }
 
     if let Some(precision) = args.precision {
-        let precision = match precision {
-            Count::Integer(int) => int,
-            Count::Parameter(_) => panic!("parameter counts are not supported right now"),
+        opts = match precision {
+            Count::Integer(precision) => quote! { #prefix::WithPrecision<#opts, #precision> },
+            Count::Parameter(_) | Count::Star => {
+                quote! { #prefix::WithRuntimePrecision<#opts> }
+            }
         };
-        opts = quote! { #prefix::WithPrecision<#opts, #precision> };
     }
 
     if let Some(Sign::Plus) = args.sign {
@@ -243,15 +345,20 @@ fn opt_ty_tokens(scope: Scoped<'_, FormatterArgs<'_>>) -> TokenStream {
         opts = quote! { #prefix::WithDebugUpperHex<#opts> };
     }
 
+    if args.style.is_some() {
+        opts = quote! { #prefix::WithStyle<#opts> };
+    }
+
     opts
 }
 
+/// The option carrying the alignment.
 impl ToTokens for Align {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         tokens.extend(match self {
-            Self::Left => quote! { 1 },
-            Self::Center => quote! { 2 },
-            Self::Right => quote! { 3 },
+            Self::Left => quote! { WithAlignLeft },
+            Self::Center => quote! { WithAlignCenter },
+            Self::Right => quote! { WithAlignRight },
         })
     }
 }
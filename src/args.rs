@@ -1,12 +1,24 @@
 use crate::{FmtOpts, Formatter, Result, Write};
 pub trait Arguments {
     fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result;
+
+    /// An estimate of how many bytes `fmt` writes, for reserving capacity up front. The literal
+    /// pieces are known at compile time, values add what their trait impls estimate.
+    #[inline]
+    fn size_hint(&self) -> usize {
+        0
+    }
 }
 
 impl<A: Arguments> Arguments for &A {
     fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
         <A as Arguments>::fmt(*self, f)
     }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        <A as Arguments>::size_hint(*self)
+    }
 }
 
 macro_rules! tuple_args {
@@ -26,6 +38,13 @@ macro_rules! tuple_args {
                 )*
                 Ok(())
             }
+
+            #[allow(non_snake_case)]
+            #[inline]
+            fn size_hint(&self) -> usize {
+                let ($first, $($rest),*) = self;
+                Arguments::size_hint($first) $(+ Arguments::size_hint($rest))*
+            }
         }
 
         tuple_args!($($rest)*);
@@ -52,6 +71,11 @@ impl Arguments for Str {
     fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
         f.write_str(self.0)
     }
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        self.0.len()
+    }
 }
 
 macro_rules! traits {
@@ -61,14 +85,34 @@ macro_rules! traits {
 
             pub trait $trait {
                 fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result;
+
+                /// An estimate of how many bytes `fmt` writes without padding, for reserving
+                /// capacity up front. Nothing by default. Not part of the public API.
+                #[doc(hidden)]
+                #[inline]
+                fn __mono_fmt_size_hint(&self) -> usize {
+                    0
+                }
             }
 
             impl<T: $trait + ?Sized, O: FmtOpts> Arguments for $arg_name<'_, T, O> {
                 fn fmt<W: Write, OldOpts: FmtOpts>(&self, f: &mut Formatter<W, OldOpts>) -> Result {
+                    // the style goes around the padding as well, like a cell of a table
+                    let style = self.1.style();
+                    if !style.is_plain() {
+                        return f.with_style(style, |f| <T as $trait>::fmt(&self.0, &mut f.wrap_with(&self.1)));
+                    }
+
                     let mut f = f.wrap_with(&self.1);
 
                     <T as $trait>::fmt(&self.0, &mut f)
                 }
+
+                /// Padding writes at least `width` chars, so at least as many bytes.
+                #[inline]
+                fn size_hint(&self) -> usize {
+                    <T as $trait>::__mono_fmt_size_hint(self.0).max(self.1.width().unwrap_or(0))
+                }
             }
         )*
 
@@ -78,6 +122,11 @@ macro_rules! traits {
                 fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
                     <T as $trait>::fmt(&self, f)
                 }
+
+                #[inline]
+                fn __mono_fmt_size_hint(&self) -> usize {
+                    <T as $trait>::__mono_fmt_size_hint(self)
+                }
             }
 
             $( #[cfg(all(any(), $no_reference_blanket_impl))] )?
@@ -85,6 +134,11 @@ macro_rules! traits {
                 fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
                     <T as $trait>::fmt(&self, f)
                 }
+
+                #[inline]
+                fn __mono_fmt_size_hint(&self) -> usize {
+                    <T as $trait>::__mono_fmt_size_hint(self)
+                }
             }
 
         )*
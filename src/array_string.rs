@@ -0,0 +1,187 @@
+//! A string on the stack, for formatting without an allocator.
+
+use core::{fmt, ops::Deref};
+
+use crate::{Error, ErrorKind, FmtOpts, Formatter, Result, Write};
+
+/// What an [`ArrayString`] does with output that doesn't fit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Overflow {
+    /// Fails the write with [`ErrorKind::Capacity`], keeping what was written before.
+    Error,
+    /// Keeps as much as fits, cut at a char boundary, and drops everything after.
+    Truncate,
+}
+
+/// A string of at most `N` bytes, stored inline. [`format_array!`](crate::format_array!) formats into it.
+///
+/// ```
+/// use mono_fmt::{ArrayString, ErrorKind};
+///
+/// let s: ArrayString<8> = mono_fmt::format_array!("{:>4}", 12).unwrap();
+/// assert_eq!(&*s, "  12");
+///
+/// let err = mono_fmt::format_array!(4; "{}", "too long").unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::Capacity);
+///
+/// let mut s = ArrayString::<2>::truncating();
+/// mono_fmt::write!(s, "{}", "héllo").unwrap();
+/// // `é` takes two bytes, so only `h` fits
+/// assert_eq!(s, "h");
+/// assert!(s.is_truncated());
+/// ```
+#[derive(Clone, Copy)]
+pub struct ArrayString<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+    overflow: Overflow,
+    truncated: bool,
+}
+
+impl<const N: usize> ArrayString<N> {
+    /// An empty string failing writes that don't fit.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::with_overflow(Overflow::Error)
+    }
+
+    /// An empty string cutting off what doesn't fit.
+    #[must_use]
+    pub const fn truncating() -> Self {
+        Self::with_overflow(Overflow::Truncate)
+    }
+
+    #[must_use]
+    pub const fn with_overflow(overflow: Overflow) -> Self {
+        Self {
+            buf: [0; N],
+            len: 0,
+            overflow,
+            truncated: false,
+        }
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        // SAFETY: only whole `str`s or prefixes of them cut at a char boundary are copied in
+        unsafe { core::str::from_utf8_unchecked(&self.buf[..self.len]) }
+    }
+
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    #[must_use]
+    pub const fn remaining(&self) -> usize {
+        N - self.len
+    }
+
+    /// Whether output was cut off, with [`Overflow::Truncate`].
+    #[must_use]
+    pub const fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.truncated = false;
+    }
+}
+
+impl<const N: usize> Default for ArrayString<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Write for ArrayString<N> {
+    fn write_str(&mut self, str: &str) -> Result {
+        if self.truncated {
+            return Ok(());
+        }
+
+        let fits = if str.len() <= self.remaining() {
+            str.len()
+        } else {
+            match self.overflow {
+                Overflow::Error => return Err(Error::new(ErrorKind::Capacity)),
+                Overflow::Truncate => {
+                    self.truncated = true;
+                    (0..=self.remaining())
+                        .rev()
+                        .find(|&idx| str.is_char_boundary(idx))
+                        .unwrap_or(0)
+                }
+            }
+        };
+
+        self.buf[self.len..self.len + fits].copy_from_slice(&str.as_bytes()[..fits]);
+        self.len += fits;
+        Ok(())
+    }
+}
+
+impl<const N: usize> Deref for ArrayString<N> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> AsRef<str> for ArrayString<N> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> PartialEq for ArrayString<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const N: usize> Eq for ArrayString<N> {}
+
+impl<const N: usize> PartialEq<str> for ArrayString<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for ArrayString<N> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<const N: usize> core::hash::Hash for ArrayString<N> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl<const N: usize> fmt::Debug for ArrayString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> fmt::Display for ArrayString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> crate::Debug for ArrayString<N> {
+    fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+        crate::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> crate::Display for ArrayString<N> {
+    fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+        crate::Display::fmt(self.as_str(), f)
+    }
+}
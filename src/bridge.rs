@@ -0,0 +1,220 @@
+//! Adapters between the `core::fmt` traits and the ones of this crate.
+
+use core::fmt as core_fmt;
+
+use crate::{
+    opts::{Alignment, RuntimeOpts},
+    Binary, Debug, Display, Error, FmtOpts, Formatter, LowerExp, LowerHex, Octal, Pointer, Result,
+    UpperExp, UpperHex, Write,
+};
+
+/// Formats a value implementing the `core::fmt` traits with the traits of this crate.
+///
+/// The flags are carried over to the `core::fmt::Formatter` and the value is formatted once.
+/// With an alignment, the output is padded as a whole, even if the value would ignore the
+/// width or pad its parts. Otherwise `core` pads with spaces, as it only takes a fill along
+/// with an alignment, and with the `0` flag it pads numbers after their sign.
+///
+/// ```
+/// # use mono_fmt::{format, Std};
+/// let addr = std::net::Ipv4Addr::LOCALHOST;
+/// assert_eq!(format!("{:*^13}", Std(&addr)), "**127.0.0.1**");
+/// ```
+#[derive(Clone, Copy)]
+pub struct Std<'a, T: ?Sized>(pub &'a T);
+
+/// Formats a value implementing the traits of this crate with the `core::fmt` traits,
+/// for `std::format!`, logging crates and everything else expecting them.
+///
+/// The options are carried over, except for the `x?` and `X?` debug flags, which `core`
+/// doesn't expose.
+///
+/// ```
+/// # use mono_fmt::Mono;
+/// #[derive(mono_fmt::Debug)]
+/// struct Point(i32, i32);
+///
+/// assert_eq!(std::format!("{:?}", Mono(&Point(1, 2))), "Point(1, 2)");
+/// ```
+#[derive(Clone, Copy)]
+pub struct Mono<'a, T: ?Sized>(pub &'a T);
+
+/// The flags passed to `core`, which only takes them from a literal.
+#[derive(Clone, Copy)]
+struct Spec {
+    sign_plus: bool,
+    alternate: bool,
+    zero: bool,
+    precision: Option<usize>,
+    /// `Some(true)` for upper hex
+    debug_hex: Option<bool>,
+}
+
+impl Spec {
+    fn new<W, O: FmtOpts>(f: &Formatter<W, O>) -> Self {
+        Self {
+            sign_plus: f.sign_plus(),
+            alternate: f.alternate(),
+            zero: f.sign_aware_zero_pad(),
+            precision: f.precision(),
+            debug_hex: if f.debug_upper_hex() {
+                Some(true)
+            } else if f.debug_lower_hex() {
+                Some(false)
+            } else {
+                None
+            },
+        }
+    }
+}
+
+/// Keeps the error of the writer, which `core_fmt::Error` can't carry.
+struct KeepError<'a, W> {
+    out: &'a mut W,
+    error: Option<Error>,
+}
+
+impl<W: Write> core_fmt::Write for KeepError<'_, W> {
+    fn write_str(&mut self, s: &str) -> core_fmt::Result {
+        self.out.write_str(s).map_err(|err| {
+            self.error = Some(err);
+            core_fmt::Error
+        })
+    }
+}
+
+/// Writes a value with `core`. Implemented for the `core::fmt` trait objects, so that the
+/// `core::format_args!` per combination of flags exist once per trait instead of per type.
+trait WriteCore {
+    fn write_core(
+        &self,
+        out: &mut dyn core_fmt::Write,
+        spec: &Spec,
+        width: usize,
+    ) -> core_fmt::Result;
+}
+
+fn write_kept<W: Write, V: WriteCore + ?Sized>(
+    out: &mut W,
+    value: &V,
+    spec: &Spec,
+    width: usize,
+) -> Result {
+    let mut out = KeepError { out, error: None };
+    value
+        .write_core(&mut out, spec, width)
+        .map_err(|err| out.error.unwrap_or_else(|| err.into()))
+}
+
+/// Holds the output of `core` until it is padded.
+#[cfg(feature = "alloc")]
+type Buffer = alloc::string::String;
+#[cfg(not(feature = "alloc"))]
+type Buffer = crate::ArrayString<256>;
+
+fn write_core<W: Write, O: FmtOpts, V: WriteCore + ?Sized>(
+    f: &mut Formatter<W, O>,
+    value: &V,
+) -> Result {
+    let spec = Spec::new(f);
+    // a width of zero never pads, just like no width
+    let width = f.width().unwrap_or(0);
+
+    // `core` pads zeros after the sign, and only the value knows its default alignment
+    if width == 0 || spec.zero || f.align() == Alignment::Unknown {
+        return write_kept(&mut f.buf, value, &spec, width);
+    }
+
+    let mut buffer = Buffer::new();
+    write_kept(&mut buffer, value, &spec, 0)?;
+    let padding = width.saturating_sub(buffer.chars().count());
+    let post_padding = f.padding(padding, Alignment::Left, f.fill(), f.align())?;
+    f.buf.write_str(&buffer)?;
+    post_padding.write(f)
+}
+
+/// Builds the format spec for `core` one step at a time, as it only takes the flags from a
+/// literal. A step is either a literal or a `(scrutinee, { pattern => literal [named args], .. })`
+/// branching on an option, and expands to a `core::format_args!` per combination.
+macro_rules! dispatch {
+    (@step $args:tt $spec:tt []) => {
+        dispatch!(@emit $args $spec)
+    };
+    (@step $args:tt [$($spec:literal)*] [$lit:literal $($rest:tt)*]) => {
+        dispatch!(@step $args [$($spec)* $lit] [$($rest)*])
+    };
+    (@step $args:tt $spec:tt [$first:tt $($rest:tt)*]) => {
+        dispatch!(@branch $args $spec $first [$($rest)*])
+    };
+    (@branch $args:tt $spec:tt (
+        $scrutinee:expr,
+        { $($pat:pat => $lit:literal $([$($named:tt)*])?),* $(,)? }
+    ) $rest:tt) => {
+        match $scrutinee {
+            $($pat => dispatch!(@next $args $spec $lit [$($($named)*)?] $rest),)*
+        }
+    };
+    (@next ($out:expr, $value:expr, [$($named:tt)*]) [$($spec:literal)*] $lit:literal [$($new:tt)*] $rest:tt) => {
+        dispatch!(@step ($out, $value, [$($named)* $($new)*]) [$($spec)* $lit] $rest)
+    };
+    (@emit ($out:expr, $value:expr, [$($named:tt)*]) [$($spec:literal)*]) => {
+        $out.write_fmt(core::format_args!(
+            concat!("{value:", $($spec,)* "}"),
+            value = $value,
+            $($named)*
+        ))
+    };
+}
+
+macro_rules! bridge {
+    ($($trait:ident($spec:ident): $ty:tt;)*) => {
+        $(
+            impl WriteCore for dyn core_fmt::$trait + '_ {
+                fn write_core(
+                    &self,
+                    out: &mut dyn core_fmt::Write,
+                    $spec: &Spec,
+                    width: usize,
+                ) -> core_fmt::Result {
+                    dispatch!(@step (out, self, [width = width,]) [] [
+                        ($spec.sign_plus, { true => "+", false => "" })
+                        ($spec.alternate, { true => "#", false => "" })
+                        ($spec.zero, { true => "0", false => "" })
+                        "width$"
+                        ($spec.precision, {
+                            Some(precision) => ".precision$" [precision = precision,],
+                            None => "",
+                        })
+                        $ty
+                    ])
+                }
+            }
+
+            impl<T: core_fmt::$trait + ?Sized> $trait for Std<'_, T> {
+                fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+                    write_core(f, &self.0 as &dyn core_fmt::$trait)
+                }
+            }
+
+            impl<T: $trait + ?Sized> core_fmt::$trait for Mono<'_, T> {
+                fn fmt(&self, f: &mut core_fmt::Formatter<'_>) -> core_fmt::Result {
+                    let opts = RuntimeOpts::from_core(f);
+                    let mut f = Formatter { buf: f, opts };
+                    <T as $trait>::fmt(self.0, &mut f).map_err(core_fmt::Error::from)
+                }
+            }
+        )*
+    };
+}
+
+bridge! {
+    Debug(spec): (spec.debug_hex, { None => "?", Some(false) => "x?", Some(true) => "X?" });
+    Display(spec): "";
+    Binary(spec): "b";
+    Octal(spec): "o";
+    LowerHex(spec): "x";
+    UpperHex(spec): "X";
+    LowerExp(spec): "e";
+    UpperExp(spec): "E";
+    Pointer(spec): "p";
+}
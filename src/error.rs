@@ -0,0 +1,113 @@
+use core::fmt;
+
+/// What went wrong while formatting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The destination is full, like a `&mut [u8]` too short for the output.
+    Capacity,
+    /// The destination failed, like a closed socket. `IoWriter` keeps the
+    /// `std::io::Error`.
+    Io,
+    /// An implementation of a formatting trait gave up, this is also what a
+    /// `core::fmt::Error` turns into.
+    User,
+}
+
+/// The error of formatting, saying what went wrong with its [`ErrorKind`].
+///
+/// ```
+/// use mono_fmt::ErrorKind;
+///
+/// let mut buf = [0; 4];
+/// let err = mono_fmt::write!(&mut buf[..], "{}", 123_456).unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::Capacity);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Error {
+    kind: ErrorKind,
+}
+
+impl Error {
+    #[must_use]
+    pub fn new(kind: ErrorKind) -> Self {
+        Self { kind }
+    }
+
+    #[must_use]
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+}
+
+impl Default for Error {
+    /// A [`ErrorKind::User`] error, for implementations that just give up.
+    fn default() -> Self {
+        Self::new(ErrorKind::User)
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Self {
+        Self::new(kind)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ErrorKind::Capacity => {
+                f.write_str("the destination is too small for the formatted output")
+            }
+            ErrorKind::Io => f.write_str("writing the formatted output failed"),
+            ErrorKind::User => f.write_str("an error occurred when formatting an argument"),
+        }
+    }
+}
+
+impl From<fmt::Error> for Error {
+    fn from(_: fmt::Error) -> Self {
+        Self::new(ErrorKind::User)
+    }
+}
+
+impl From<Error> for fmt::Error {
+    fn from(_: Error) -> Self {
+        Self
+    }
+}
+
+#[cfg(feature = "std")]
+mod std_impls {
+    use std::io;
+
+    use super::{Error, ErrorKind};
+
+    impl std::error::Error for Error {}
+
+    impl From<io::Error> for Error {
+        /// An [`ErrorKind::Io`], or [`ErrorKind::Capacity`] for the `WriteZero` of a full buffer.
+        fn from(io: io::Error) -> Self {
+            Self::of_io(&io)
+        }
+    }
+
+    impl Error {
+        pub(crate) fn of_io(io: &io::Error) -> Self {
+            Self::new(match io.kind() {
+                io::ErrorKind::WriteZero => ErrorKind::Capacity,
+                _ => ErrorKind::Io,
+            })
+        }
+    }
+
+    impl From<Error> for io::Error {
+        /// Wraps the error like `io::Write` does, `WriteZero` for a full destination.
+        fn from(err: Error) -> Self {
+            match err.kind {
+                ErrorKind::Capacity => io::Error::new(io::ErrorKind::WriteZero, err),
+                _ => io::Error::other(err),
+            }
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{Debug, FmtOpts, Result, Write};
+use crate::{Debug, FmtOpts, Result, Style, Write};
 
 pub struct Formatter<W, O> {
     pub(crate) buf: W,
@@ -14,6 +14,22 @@ impl<W: Write, O: FmtOpts> core::fmt::Write for Formatter<W, O> {
     }
 }
 
+impl<W: Write, O: FmtOpts> Write for Formatter<W, O> {
+    fn write_str(&mut self, str: &str) -> Result {
+        self.buf.write_str(str)
+    }
+
+    fn write_char(&mut self, char: char) -> Result {
+        self.buf.write_char(char)
+    }
+
+    /// Layers `style` over the style of this formatter, so that nested formatting keeps the
+    /// colors it doesn't set itself.
+    fn write_style(&mut self, style: Style) -> Result {
+        self.buf.write_style(self.opts.style().patch(style))
+    }
+}
+
 impl<W> Formatter<W, ()> {
     pub fn new(buf: W) -> Self {
         Self { buf, opts: () }
@@ -132,6 +148,15 @@ impl<W: Write> fmt::Write for PadAdapter<'_, '_, W> {
 
         Ok(())
     }
+
+    /// Writes a pending indentation first, so that it isn't styled.
+    fn write_style(&mut self, style: Style) -> fmt::Result {
+        if self.state.on_newline {
+            self.buf.write_str("    ")?;
+            self.state.on_newline = false;
+        }
+        self.buf.write_style(style)
+    }
 }
 
 #[must_use = "must eventually call `finish()` on Debug builders"]
@@ -153,9 +178,9 @@ pub(super) fn debug_struct_new<'a, W: Write, O: FmtOpts>(
     }
 }
 
-impl<'a, W: Write, O: FmtOpts> DebugStruct<'a, W, O> {
+impl<W: Write, O: FmtOpts> DebugStruct<'_, W, O> {
     pub fn field(&mut self, name: &str, value: &impl Debug) -> &mut Self {
-        self.result = self.result.and_then(|_| {
+        self.result = self.result.and_then(|()| {
             if self.is_pretty() {
                 if !self.has_fields {
                     self.fmt.write_str(" {\n")?;
@@ -181,7 +206,7 @@ impl<'a, W: Write, O: FmtOpts> DebugStruct<'a, W, O> {
     }
 
     pub fn finish_non_exhaustive(&mut self) -> fmt::Result {
-        self.result = self.result.and_then(|_| {
+        self.result = self.result.and_then(|()| {
             if self.has_fields {
                 if self.is_pretty() {
                     let mut slot = None;
@@ -201,7 +226,7 @@ impl<'a, W: Write, O: FmtOpts> DebugStruct<'a, W, O> {
 
     pub fn finish(&mut self) -> fmt::Result {
         if self.has_fields {
-            self.result = self.result.and_then(|_| {
+            self.result = self.result.and_then(|()| {
                 if self.is_pretty() {
                     self.fmt.write_str("}")
                 } else {
@@ -239,9 +264,9 @@ pub(super) fn debug_tuple_new<'a, W: Write, O: FmtOpts>(
     }
 }
 
-impl<'a, W: Write, O: FmtOpts> DebugTuple<'a, W, O> {
+impl<W: Write, O: FmtOpts> DebugTuple<'_, W, O> {
     pub fn field(&mut self, value: &impl Debug) -> &mut Self {
-        self.result = self.result.and_then(|_| {
+        self.result = self.result.and_then(|()| {
             if self.is_pretty() {
                 if self.fields == 0 {
                     self.fmt.write_str("(\n")?;
@@ -264,7 +289,7 @@ impl<'a, W: Write, O: FmtOpts> DebugTuple<'a, W, O> {
 
     pub fn finish(&mut self) -> fmt::Result {
         if self.fields > 0 {
-            self.result = self.result.and_then(|_| {
+            self.result = self.result.and_then(|()| {
                 if self.fields == 1 && self.empty_name && !self.is_pretty() {
                     self.fmt.write_str(",")?;
                 }
@@ -285,9 +310,9 @@ struct DebugInner<'a, W, O> {
     has_fields: bool,
 }
 
-impl<'a, W: Write, O: FmtOpts> DebugInner<'a, W, O> {
+impl<W: Write, O: FmtOpts> DebugInner<'_, W, O> {
     fn entry(&mut self, entry: &impl Debug) {
-        self.result = self.result.and_then(|_| {
+        self.result = self.result.and_then(|()| {
             if self.is_pretty() {
                 if !self.has_fields {
                     self.fmt.write_str("\n")?;
@@ -332,7 +357,7 @@ pub(super) fn debug_set_new<W: Write, O: FmtOpts>(
     }
 }
 
-impl<'a, W: Write, O: FmtOpts> DebugSet<'a, W, O> {
+impl<W: Write, O: FmtOpts> DebugSet<'_, W, O> {
     pub fn entry(&mut self, entry: &impl Debug) -> &mut Self {
         self.inner.entry(entry);
         self
@@ -352,7 +377,7 @@ impl<'a, W: Write, O: FmtOpts> DebugSet<'a, W, O> {
     pub fn finish(&mut self) -> fmt::Result {
         self.inner
             .result
-            .and_then(|_| self.inner.fmt.write_str("}"))
+            .and_then(|()| self.inner.fmt.write_str("}"))
     }
 }
 
@@ -375,7 +400,7 @@ pub(super) fn debug_list_new<W: Write, O: FmtOpts>(
     }
 }
 
-impl<'a, W: Write, O: FmtOpts> DebugList<'a, W, O> {
+impl<W: Write, O: FmtOpts> DebugList<'_, W, O> {
     pub fn entry(&mut self, entry: &impl Debug) -> &mut Self {
         self.inner.entry(entry);
         self
@@ -395,7 +420,7 @@ impl<'a, W: Write, O: FmtOpts> DebugList<'a, W, O> {
     pub fn finish(&mut self) -> fmt::Result {
         self.inner
             .result
-            .and_then(|_| self.inner.fmt.write_str("]"))
+            .and_then(|()| self.inner.fmt.write_str("]"))
     }
 }
 
@@ -423,13 +448,13 @@ pub(super) fn debug_map_new<W: Write, O: FmtOpts>(
     }
 }
 
-impl<'a, W: Write, O: FmtOpts> DebugMap<'a, W, O> {
+impl<W: Write, O: FmtOpts> DebugMap<'_, W, O> {
     pub fn entry(&mut self, key: &impl Debug, value: &impl Debug) -> &mut Self {
         self.key(key).value(value)
     }
 
     pub fn key(&mut self, key: &impl Debug) -> &mut Self {
-        self.result = self.result.and_then(|_| {
+        self.result = self.result.and_then(|()| {
             assert!(
                 !self.has_key,
                 "attempted to begin a new map entry \
@@ -461,7 +486,7 @@ impl<'a, W: Write, O: FmtOpts> DebugMap<'a, W, O> {
     }
 
     pub fn value(&mut self, value: impl Debug) -> &mut Self {
-        self.result = self.result.and_then(|_| {
+        self.result = self.result.and_then(|()| {
             assert!(
                 self.has_key,
                 "attempted to format a map value before its key"
@@ -497,7 +522,7 @@ impl<'a, W: Write, O: FmtOpts> DebugMap<'a, W, O> {
     }
 
     pub fn finish(&mut self) -> fmt::Result {
-        self.result.and_then(|_| {
+        self.result.and_then(|()| {
             assert!(
                 !self.has_key,
                 "attempted to finish a map with a partial entry"
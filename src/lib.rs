@@ -5,17 +5,25 @@
     clippy::missing_errors_doc,
     clippy::wildcard_imports,
     clippy::module_name_repetitions,
-    clippy::single_match_else,
+    clippy::single_match_else
 )]
 
 extern crate alloc;
 
 mod args;
+mod array_string;
+mod bridge;
+mod error;
 mod formatter;
 mod opts;
 mod rust_core_impl;
+mod style;
 mod write;
 
+/// Like `core::format_args!`, the format string is checked at compile time.
+///
+/// The errors are worded like rustc's. Errors about a part of the format string only point at
+/// that part on nightly compilers, stable ones point at the whole literal.
 #[macro_export]
 macro_rules! format_args {
     ($($tt:tt)*) => {
@@ -23,17 +31,22 @@ macro_rules! format_args {
     };
 }
 
+pub use mono_fmt_macro::{Debug, Display};
+
+#[cfg(feature = "std")]
+pub use crate::write::IoWriter;
 pub use crate::{
     args::{pub_exports::*, Arguments},
+    array_string::{ArrayString, Overflow},
+    bridge::{Mono, Std},
+    error::{Error, ErrorKind},
     formatter::{DebugList, DebugMap, DebugSet, DebugStruct, DebugTuple, Formatter},
-    opts::FmtOpts,
+    opts::{Alignment, FmtOpts, RuntimeOpts},
+    style::{Ansi, Attributes, Color, Style},
 };
 
 pub type Result = core::result::Result<(), Error>;
 
-#[derive(Debug, Clone, Copy)]
-pub struct Error;
-
 pub trait Write {
     fn write_str(&mut self, str: &str) -> Result;
 
@@ -41,13 +54,20 @@ pub trait Write {
         let mut buf = [0; 4];
         self.write_str(char.encode_utf8(&mut buf))
     }
+
+    /// Switches to `style` for what is written next. Plain text writers ignore it, which is the
+    /// default, [`Ansi`] writes it as escape codes.
+    fn write_style(&mut self, style: Style) -> Result {
+        let _ = style;
+        Ok(())
+    }
 }
 
 pub mod helpers {
     #[cfg(feature = "alloc")]
     use alloc::string::String;
 
-    use crate::{Arguments, Formatter, Result, Write};
+    use crate::{Arguments, ArrayString, Error, Formatter, Result, Write};
 
     pub fn write<W: Write, A: Arguments>(buffer: W, args: A) -> Result {
         let mut fmt = Formatter::new(buffer);
@@ -56,10 +76,52 @@ pub mod helpers {
 
     #[cfg(feature = "alloc")]
     pub fn format<A: Arguments>(args: A) -> String {
-        let mut string = String::new();
+        let mut string = String::with_capacity(args.size_hint());
         write(&mut string, args).unwrap();
         string
     }
+
+    /// Formats into an [`ArrayString`], failing if the output doesn't fit.
+    pub fn format_array<const N: usize, A: Arguments>(
+        args: A,
+    ) -> core::result::Result<ArrayString<N>, Error> {
+        let mut string = ArrayString::new();
+        write(&mut string, args)?;
+        Ok(string)
+    }
+
+    #[cfg(feature = "std")]
+    pub fn print<A: Arguments>(args: A) {
+        print_to(std::io::stdout().lock(), args, false, "stdout");
+    }
+
+    #[cfg(feature = "std")]
+    pub fn println<A: Arguments>(args: A) {
+        print_to(std::io::stdout().lock(), args, true, "stdout");
+    }
+
+    #[cfg(feature = "std")]
+    pub fn eprint<A: Arguments>(args: A) {
+        print_to(std::io::stderr().lock(), args, false, "stderr");
+    }
+
+    #[cfg(feature = "std")]
+    pub fn eprintln<A: Arguments>(args: A) {
+        print_to(std::io::stderr().lock(), args, true, "stderr");
+    }
+
+    /// Writes to `out`, panicking like `std::print!` when that fails.
+    #[cfg(feature = "std")]
+    pub(crate) fn print_to<W: Write, A: Arguments>(out: W, args: A, newline: bool, name: &str) {
+        let result = if newline {
+            write(out, (args, crate::args::Str("\n")))
+        } else {
+            write(out, args)
+        };
+        if let Err(err) = result {
+            panic!("failed printing to {name}: {err}");
+        }
+    }
 }
 
 /// Not part of the public API.
@@ -70,7 +132,19 @@ pub mod _private {
     pub use crate::{
         args::{macro_exports::*, Str},
         opts::exports::*,
+        style::{Attributes, Color, Style},
     };
+    use crate::{helpers, Arguments, Result, Write};
+
+    /// Lets `write!` take its destination by method call autoref, without clashing with
+    /// `core::fmt::Write::write_fmt` or `std::io::Write::write_fmt` in scope.
+    pub trait WriteFmt: Write + Sized {
+        fn __mono_fmt_write_fmt<A: Arguments>(&mut self, args: A) -> Result {
+            helpers::write(self, args)
+        }
+    }
+
+    impl<W: Write> WriteFmt for W {}
 }
 
 #[cfg(feature = "alloc")]
@@ -81,10 +155,77 @@ macro_rules! format {
     };
 }
 
+/// Like [`format!`], but into an [`ArrayString`] instead of allocating. The capacity is inferred
+/// or comes first, like `format_array!(16; "{}", x)`, and the output must fit.
+#[macro_export]
+macro_rules! format_array {
+    ($capacity:expr; $($tt:tt)*) => {
+        $crate::helpers::format_array::<{ $capacity }, _>($crate::format_args!($($tt)*))
+    };
+    ($($tt:tt)*) => {
+        $crate::helpers::format_array($crate::format_args!($($tt)*))
+    };
+}
+
+#[macro_export]
+macro_rules! write {
+    ($dst:expr, $($tt:tt)*) => {{
+        use $crate::_private::WriteFmt as _;
+        $dst.__mono_fmt_write_fmt($crate::format_args!($($tt)*))
+    }};
+}
+
+#[macro_export]
+macro_rules! writeln {
+    ($dst:expr $(,)?) => {
+        $crate::write!($dst, "\n")
+    };
+    ($dst:expr, $($tt:tt)*) => {{
+        use $crate::_private::WriteFmt as _;
+        $dst.__mono_fmt_write_fmt(($crate::format_args!($($tt)*), $crate::_private::Str("\n")))
+    }};
+}
+
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! print {
+    ($($tt:tt)*) => {
+        $crate::helpers::print($crate::format_args!($($tt)*))
+    };
+}
+
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! println {
+    () => {
+        $crate::print!("\n")
+    };
+    ($($tt:tt)*) => {
+        $crate::helpers::println($crate::format_args!($($tt)*))
+    };
+}
+
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! eprint {
+    ($($tt:tt)*) => {
+        $crate::helpers::eprint($crate::format_args!($($tt)*))
+    };
+}
+
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! eprintln {
+    () => {
+        $crate::eprint!("\n")
+    };
+    ($($tt:tt)*) => {
+        $crate::helpers::eprintln($crate::format_args!($($tt)*))
+    };
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::format;
-
     #[test]
     fn hello_world() {
         let result = format!("Hello, World");
@@ -115,6 +256,36 @@ mod tests {
         assert_eq!(result, "a: 32523532");
     }
 
+    #[test]
+    #[cfg(feature = "std")]
+    fn print() {
+        // only checks that the macros expand and don't panic, writing nothing visible
+        print!("{}", "");
+        eprint!("{}", "");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn print_to() {
+        use crate::helpers::print_to;
+
+        let mut out = String::new();
+        print_to(&mut out, format_args!("{} {}", 1, "a"), false, "stdout");
+        print_to(&mut out, format_args!("{}", 2), true, "stdout");
+        print_to(&mut out, format_args!("b"), true, "stdout");
+        assert_eq!(out, "1 a2\nb\n");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    #[should_panic(
+        expected = "failed printing to stderr: the destination is too small for the formatted output"
+    )]
+    fn print_to_failing() {
+        let mut buf = [0; 2];
+        crate::helpers::print_to(&mut buf[..], format_args!("{}", 123), true, "stderr");
+    }
+
     #[test]
     fn escape() {
         let result = format!("a: {{{}}}", 6);
@@ -123,6 +294,7 @@ mod tests {
 }
 
 /// this should really not end up in the final code lmao
+#[cfg(feature = "std")]
 #[doc(hidden)]
 pub mod uwu {
     #![allow(dead_code)]
@@ -1,6 +1,7 @@
-use crate::Formatter;
+use crate::{Formatter, Result, Style};
 
-#[derive(Debug, Clone, Copy)]
+/// Where a value goes when it is padded to the width, `Unknown` if the format spec doesn't say.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Alignment {
     Left,
     Center,
@@ -13,6 +14,14 @@ mod sealed {
 
     impl SealedOpts for () {}
     impl<O: super::FmtOpts> SealedOpts for &'_ O {}
+    impl SealedOpts for super::RuntimeOpts {}
+}
+
+/// Expands to the second argument, used to repeat something once per optional macro fragment.
+macro_rules! ignore_first {
+    ($ignored:tt, $($tt:tt)*) => {
+        $($tt)*
+    };
 }
 
 macro_rules! options {
@@ -22,11 +31,17 @@ macro_rules! options {
                 $($default:tt)*
             }
 
-            struct $with_name:ident$(<$(const $const_gen_name:ident: $with_ty:ty),*>)? {
-                $($struct_body:tt)*
-            }
+            $(
+                struct $with_name:ident$(<$(const $const_gen_name:ident: $with_ty:ty),*>)? $(($value_name:ident: $value_ty:ty))? {
+                    $($struct_body:tt)*
+                }
+            )+
         )*
     ) => {
+        /// The options of a format spec, like the width or the alignment, mostly encoded in the type.
+        ///
+        /// Implementations of the formatting traits are generic over it and read the options
+        /// through the getters of [`Formatter`]. It is sealed, the options are built by the macros.
         // FIXME: We can get rid of this Copy can't we
         pub trait FmtOpts: sealed::SealedOpts + Copy {
             #[doc(hidden)]
@@ -107,32 +122,35 @@ macro_rules! options {
         }
 
         $(
-            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-            pub struct $with_name<I, $($(const $const_gen_name: $with_ty),*)?>(#[doc(hidden)] pub I);
+            $(
+                #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+                pub struct $with_name<I, $($(const $const_gen_name: $with_ty),*)?>(#[doc(hidden)] pub I, $(#[doc(hidden)] pub $value_ty)?);
 
-            impl<I: FmtOpts, $($(const $const_gen_name: $with_ty),*)?> sealed::SealedOpts for $with_name<I, $($($const_gen_name),*)?> {}
+                impl<I: FmtOpts, $($(const $const_gen_name: $with_ty),*)?> sealed::SealedOpts for $with_name<I, $($($const_gen_name),*)?> {}
 
-            impl<I: FmtOpts, $($(const $const_gen_name: $with_ty),*)?> FmtOpts for $with_name<I, $($($const_gen_name),*)?> {
-                type Inner = I;
+                impl<I: FmtOpts, $($(const $const_gen_name: $with_ty),*)?> FmtOpts for $with_name<I, $($($const_gen_name),*)?> {
+                    type Inner = I;
 
-                type ReplaceInnermost<Replacement: FmtOpts> = $with_name<I::ReplaceInnermost<Replacement>, $($($const_gen_name),*)?>;
+                    type ReplaceInnermost<Replacement: FmtOpts> = $with_name<I::ReplaceInnermost<Replacement>, $($($const_gen_name),*)?>;
 
-                fn inner(&self) -> &Self::Inner  {
-                    &self.0
-                }
+                    fn inner(&self) -> &Self::Inner  {
+                        &self.0
+                    }
 
-                fn override_other<Other: FmtOpts>(self, other: Other) -> Self::ReplaceInnermost<Other> {
-                    $with_name(self.0.override_other(other))
-                }
+                    fn override_other<Other: FmtOpts>(self, other: Other) -> Self::ReplaceInnermost<Other> {
+                        $with_name(self.0.override_other(other), $(ignore_first!($value_name, self.1))?)
+                    }
 
-                fn $name(&self) -> $ret {
-                    $($struct_body)*
+                    fn $name(&self) -> $ret {
+                        $(let $value_name: $value_ty = self.1;)?
+                        $($struct_body)*
+                    }
                 }
-            }
+            )+
         )*
 
         pub mod exports {
-            pub use super::{$($with_name),*};
+            pub use super::{$($($with_name,)+)*};
         }
     };
 }
@@ -143,17 +161,12 @@ options!(
 
     fn width(&self) -> Option<usize> { None }
     struct WithWidth<const A: usize> { Some(A) }
+    struct WithRuntimeWidth(width: usize) { Some(width) }
 
     fn align(&self) -> Alignment { Alignment::Unknown }
-    struct WithAlign<const A: usize> {
-        match A {
-            0 => Alignment::Unknown,
-            1 => Alignment::Left,
-            2 => Alignment::Center,
-            3 => Alignment::Right,
-            _ => panic!("invalid alignment number {A}")
-        }
-    }
+    struct WithAlignLeft { Alignment::Left }
+    struct WithAlignCenter { Alignment::Center }
+    struct WithAlignRight { Alignment::Right }
 
     fn fill(&self) -> char { ' ' }
     struct WithFill<const A: char> { A }
@@ -169,10 +182,255 @@ options!(
 
     fn precision(&self) -> Option<usize> { None }
     struct WithPrecision<const A: usize> { Some(A) }
+    struct WithRuntimePrecision(precision: usize) { Some(precision) }
 
     fn debug_lower_hex(&self) -> bool { false }
     struct WithDebugLowerHex { true }
 
     fn debug_upper_hex(&self) -> bool { false }
     struct WithDebugUpperHex { true }
+
+    fn style(&self) -> Style { Style::PLAIN }
+    struct WithStyle(style: Style) { style }
 );
+
+/// Options that are all known only at runtime, for when they aren't written in a format string.
+///
+/// ```
+/// use mono_fmt::{Alignment, Display, Formatter, RuntimeOpts};
+///
+/// let widths = [3, 5];
+/// let mut out = String::new();
+/// for (cell, width) in ["a", "bc"].into_iter().zip(widths) {
+///     let opts = RuntimeOpts {
+///         width: Some(width),
+///         align: Alignment::Center,
+///         fill: '.',
+///         ..RuntimeOpts::default()
+///     };
+///     Display::fmt(cell, &mut Formatter::new(&mut out).with_opts(opts)).unwrap();
+/// }
+/// assert_eq!(out, ".a..bc..");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct RuntimeOpts {
+    pub alternate: bool,
+    pub width: Option<usize>,
+    pub align: Alignment,
+    pub fill: char,
+    pub sign_plus: bool,
+    pub sign_aware_zero_pad: bool,
+    pub sign_minus: bool,
+    pub precision: Option<usize>,
+    pub debug_lower_hex: bool,
+    pub debug_upper_hex: bool,
+    pub style: Style,
+}
+
+impl Default for RuntimeOpts {
+    /// The options of an empty format spec, like `{}`.
+    fn default() -> Self {
+        Self::from_opts(&())
+    }
+}
+
+impl RuntimeOpts {
+    /// Reads all options of `opts`.
+    pub fn from_opts<O: FmtOpts>(opts: &O) -> Self {
+        Self {
+            alternate: opts.alternate(),
+            width: opts.width(),
+            align: opts.align(),
+            fill: opts.fill(),
+            sign_plus: opts.sign_plus(),
+            sign_aware_zero_pad: opts.sign_aware_zero_pad(),
+            sign_minus: opts.sign_minus(),
+            precision: opts.precision(),
+            debug_lower_hex: opts.debug_lower_hex(),
+            debug_upper_hex: opts.debug_upper_hex(),
+            style: opts.style(),
+        }
+    }
+
+    /// Reads the options of a `core::fmt::Formatter`. `core` doesn't expose the debug hex flags
+    /// and has no styles, so they are always off.
+    pub(crate) fn from_core(f: &core::fmt::Formatter<'_>) -> Self {
+        Self {
+            alternate: f.alternate(),
+            width: f.width(),
+            align: match f.align() {
+                Some(core::fmt::Alignment::Left) => Alignment::Left,
+                Some(core::fmt::Alignment::Center) => Alignment::Center,
+                Some(core::fmt::Alignment::Right) => Alignment::Right,
+                None => Alignment::Unknown,
+            },
+            fill: f.fill(),
+            sign_plus: f.sign_plus(),
+            sign_aware_zero_pad: f.sign_aware_zero_pad(),
+            sign_minus: f.sign_minus(),
+            precision: f.precision(),
+            debug_lower_hex: false,
+            debug_upper_hex: false,
+            style: Style::PLAIN,
+        }
+    }
+}
+
+impl FmtOpts for RuntimeOpts {
+    type Inner = ();
+
+    // every option is set, so there's nothing left to fall back to
+    type ReplaceInnermost<I: FmtOpts> = Self;
+
+    fn inner(&self) -> &Self::Inner {
+        &()
+    }
+
+    fn override_other<Other: FmtOpts>(self, _: Other) -> Self::ReplaceInnermost<Other> {
+        self
+    }
+
+    fn alternate(&self) -> bool {
+        self.alternate
+    }
+
+    fn width(&self) -> Option<usize> {
+        self.width
+    }
+
+    fn align(&self) -> Alignment {
+        self.align
+    }
+
+    fn fill(&self) -> char {
+        self.fill
+    }
+
+    fn sign_plus(&self) -> bool {
+        self.sign_plus
+    }
+
+    fn sign_aware_zero_pad(&self) -> bool {
+        self.sign_aware_zero_pad
+    }
+
+    fn sign_minus(&self) -> bool {
+        self.sign_minus
+    }
+
+    fn precision(&self) -> Option<usize> {
+        self.precision
+    }
+
+    fn debug_lower_hex(&self) -> bool {
+        self.debug_lower_hex
+    }
+
+    fn debug_upper_hex(&self) -> bool {
+        self.debug_upper_hex
+    }
+
+    fn style(&self) -> Style {
+        self.style
+    }
+}
+
+/// Building a formatter with options outside of the macros. Setting an option switches to
+/// [`RuntimeOpts`], keeping the options set before.
+///
+/// ```
+/// use mono_fmt::{Alignment, Display, Formatter};
+///
+/// let mut out = String::new();
+/// let mut f = Formatter::new(&mut out)
+///     .with_width(8)
+///     .with_align(Alignment::Left)
+///     .with_precision(2);
+/// Display::fmt(&1.0, &mut f).unwrap();
+/// assert_eq!(out, "1.00    ");
+/// ```
+impl<W, O: FmtOpts> Formatter<W, O> {
+    /// Replaces the options with `opts`.
+    pub fn with_opts<ONew: FmtOpts>(self, opts: ONew) -> Formatter<W, ONew> {
+        Formatter {
+            buf: self.buf,
+            opts,
+        }
+    }
+
+    fn map_opts(self, map: impl FnOnce(&mut RuntimeOpts)) -> Formatter<W, RuntimeOpts> {
+        let mut opts = RuntimeOpts::from_opts(&self.opts);
+        map(&mut opts);
+        self.with_opts(opts)
+    }
+
+    pub fn with_alternate(self, alternate: bool) -> Formatter<W, RuntimeOpts> {
+        self.map_opts(|opts| opts.alternate = alternate)
+    }
+
+    pub fn with_width(self, width: usize) -> Formatter<W, RuntimeOpts> {
+        self.map_opts(|opts| opts.width = Some(width))
+    }
+
+    pub fn with_align(self, align: Alignment) -> Formatter<W, RuntimeOpts> {
+        self.map_opts(|opts| opts.align = align)
+    }
+
+    pub fn with_fill(self, fill: char) -> Formatter<W, RuntimeOpts> {
+        self.map_opts(|opts| opts.fill = fill)
+    }
+
+    pub fn with_sign_plus(self, sign_plus: bool) -> Formatter<W, RuntimeOpts> {
+        self.map_opts(|opts| opts.sign_plus = sign_plus)
+    }
+
+    pub fn with_sign_aware_zero_pad(self, sign_aware_zero_pad: bool) -> Formatter<W, RuntimeOpts> {
+        self.map_opts(|opts| opts.sign_aware_zero_pad = sign_aware_zero_pad)
+    }
+
+    pub fn with_sign_minus(self, sign_minus: bool) -> Formatter<W, RuntimeOpts> {
+        self.map_opts(|opts| opts.sign_minus = sign_minus)
+    }
+
+    pub fn with_precision(self, precision: usize) -> Formatter<W, RuntimeOpts> {
+        self.map_opts(|opts| opts.precision = Some(precision))
+    }
+
+    pub fn with_debug_lower_hex(self, debug_lower_hex: bool) -> Formatter<W, RuntimeOpts> {
+        self.map_opts(|opts| opts.debug_lower_hex = debug_lower_hex)
+    }
+
+    pub fn with_debug_upper_hex(self, debug_upper_hex: bool) -> Formatter<W, RuntimeOpts> {
+        self.map_opts(|opts| opts.debug_upper_hex = debug_upper_hex)
+    }
+
+    /// Like [`with_opts`](Self::with_opts) for a borrowed formatter, as in a `fmt`
+    /// implementation. `write` gets a formatter over the same writer with `opts`.
+    ///
+    /// ```
+    /// use mono_fmt::{Display, FmtOpts, Formatter, Result, RuntimeOpts, Write};
+    ///
+    /// struct Money(f64);
+    ///
+    /// impl Display for Money {
+    ///     fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+    ///         let opts = RuntimeOpts {
+    ///             precision: Some(2),
+    ///             ..RuntimeOpts::default()
+    ///         };
+    ///         f.with_runtime_opts(opts, |f| Display::fmt(&self.0, f))?;
+    ///         f.write_str(" €")
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(mono_fmt::format!("{}", Money(3.5)), "3.50 €");
+    /// ```
+    pub fn with_runtime_opts(
+        &mut self,
+        opts: RuntimeOpts,
+        write: impl FnOnce(&mut Formatter<&mut W, RuntimeOpts>) -> Result,
+    ) -> Result {
+        write(&mut self.wrap_with(&opts))
+    }
+}
@@ -18,9 +18,415 @@ impl<T: Debug> Debug for [T] {
     }
 }
 
+mod basic {
+    use core::cmp::Ordering;
+
+    use super::impl_prelude::*;
+
+    impl Display for bool {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            Display::fmt(if *self { "true" } else { "false" }, f)
+        }
+
+        fn __mono_fmt_size_hint(&self) -> usize {
+            if *self {
+                4
+            } else {
+                5
+            }
+        }
+    }
+
+    impl Debug for bool {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            Display::fmt(self, f)
+        }
+    }
+
+    impl Debug for () {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            f.pad("()")
+        }
+    }
+
+    impl<T: Debug> Debug for Option<T> {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            match self {
+                None => f.write_str("None"),
+                Some(value) => f.debug_tuple("Some").field(value).finish(),
+            }
+        }
+    }
+
+    impl<T: Debug, E: Debug> Debug for core::result::Result<T, E> {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            match self {
+                Ok(value) => f.debug_tuple("Ok").field(value).finish(),
+                Err(err) => f.debug_tuple("Err").field(err).finish(),
+            }
+        }
+    }
+
+    impl Debug for Ordering {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            f.write_str(match self {
+                Ordering::Less => "Less",
+                Ordering::Equal => "Equal",
+                Ordering::Greater => "Greater",
+            })
+        }
+    }
+}
+
+mod tuples {
+    use super::impl_prelude::*;
+
+    macro_rules! peel {
+        ($name:ident, $($other:ident,)*) => (tuple! { $($other,)* })
+    }
+
+    macro_rules! tuple {
+        () => ();
+        ( $($name:ident,)+ ) => (
+            impl<$($name: Debug),+> Debug for ($($name,)+) {
+                #[allow(non_snake_case)]
+                fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+                    let mut builder = f.debug_tuple("");
+                    let ($(ref $name,)+) = *self;
+                    $( builder.field($name); )+
+                    builder.finish()
+                }
+            }
+            peel! { $($name,)+ }
+        )
+    }
+
+    tuple! { T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, }
+}
+
+#[cfg(feature = "alloc")]
+mod collections {
+    use alloc::{
+        collections::{BTreeMap, BTreeSet, BinaryHeap, LinkedList, VecDeque},
+        vec::Vec,
+    };
+
+    use super::impl_prelude::*;
+
+    impl<T: Debug> Debug for Vec<T> {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            <[T] as Debug>::fmt(self, f)
+        }
+    }
+
+    impl<T: Debug> Debug for VecDeque<T> {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            f.debug_list().entries(self.iter()).finish()
+        }
+    }
+
+    impl<T: Debug> Debug for LinkedList<T> {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            f.debug_list().entries(self.iter()).finish()
+        }
+    }
+
+    impl<T: Debug> Debug for BinaryHeap<T> {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            f.debug_list().entries(self.iter()).finish()
+        }
+    }
+
+    impl<K: Debug, V: Debug> Debug for BTreeMap<K, V> {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            f.debug_map().entries(self.iter()).finish()
+        }
+    }
+
+    impl<T: Debug> Debug for BTreeSet<T> {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            f.debug_set().entries(self.iter()).finish()
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+mod std_collections {
+    use std::collections::{HashMap, HashSet};
+
+    use super::impl_prelude::*;
+
+    impl<K: Debug, V: Debug, S> Debug for HashMap<K, V, S> {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            f.debug_map().entries(self.iter()).finish()
+        }
+    }
+
+    impl<T: Debug, S> Debug for HashSet<T, S> {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            f.debug_set().entries(self.iter()).finish()
+        }
+    }
+}
+
+/// Prints its string as is, like a `format_args!` placeholder in core's impls.
+struct Placeholder(&'static str);
+
+impl Debug for Placeholder {
+    fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+        f.write_str(self.0)
+    }
+}
+
+mod cells {
+    use core::cell::{Cell, Ref, RefCell, RefMut};
+
+    use super::{impl_prelude::*, Placeholder};
+
+    impl<T: Copy + Debug> Debug for Cell<T> {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            f.debug_struct("Cell").field("value", &self.get()).finish()
+        }
+    }
+
+    impl<T: ?Sized + Debug> Debug for RefCell<T> {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            let mut d = f.debug_struct("RefCell");
+            match self.try_borrow() {
+                Ok(borrow) => d.field("value", &&*borrow),
+                Err(_) => d.field("value", &Placeholder("<borrowed>")),
+            };
+            d.finish()
+        }
+    }
+
+    impl<T: ?Sized + Debug> Debug for Ref<'_, T> {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            Debug::fmt(&**self, f)
+        }
+    }
+
+    impl<T: ?Sized + Display> Display for Ref<'_, T> {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            Display::fmt(&**self, f)
+        }
+    }
+
+    impl<T: ?Sized + Debug> Debug for RefMut<'_, T> {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            Debug::fmt(&**self, f)
+        }
+    }
+
+    impl<T: ?Sized + Display> Display for RefMut<'_, T> {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            Display::fmt(&**self, f)
+        }
+    }
+}
+
+mod integers {
+    //! The wrappers format like the integer in them, so `{:x?}` applies through them as well.
+
+    use core::{
+        num::{
+            NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+            NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize, Saturating, Wrapping,
+        },
+        sync::atomic::{self, Ordering},
+    };
+
+    use super::impl_prelude::*;
+
+    macro_rules! nonzero {
+        ($($ty:ident),*) => {
+            $(
+                nonzero!(@traits $ty: Debug, Display, Binary, Octal, LowerHex, UpperHex, LowerExp, UpperExp);
+            )*
+        };
+        (@traits $ty:ident: $($trait:ident),*) => {
+            $(
+                impl $trait for $ty {
+                    fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+                        $trait::fmt(&self.get(), f)
+                    }
+                }
+            )*
+        };
+    }
+
+    nonzero!(
+        NonZeroU8,
+        NonZeroU16,
+        NonZeroU32,
+        NonZeroU64,
+        NonZeroU128,
+        NonZeroUsize,
+        NonZeroI8,
+        NonZeroI16,
+        NonZeroI32,
+        NonZeroI64,
+        NonZeroI128,
+        NonZeroIsize
+    );
+
+    macro_rules! wrapper {
+        ($($ty:ident),*) => {
+            $(
+                wrapper!(@traits $ty: Debug, Display, Binary, Octal, LowerHex, UpperHex);
+            )*
+        };
+        (@traits $ty:ident: $($trait:ident),*) => {
+            $(
+                impl<T: $trait> $trait for $ty<T> {
+                    fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+                        $trait::fmt(&self.0, f)
+                    }
+                }
+            )*
+        };
+    }
+
+    wrapper!(Wrapping, Saturating);
+
+    macro_rules! atomic {
+        ($($size:literal: $($ty:ident),*;)*) => {
+            $($(
+                #[cfg(target_has_atomic = $size)]
+                impl Debug for atomic::$ty {
+                    fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+                        Debug::fmt(&self.load(Ordering::Relaxed), f)
+                    }
+                }
+            )*)*
+        };
+    }
+
+    atomic! {
+        "8": AtomicBool, AtomicU8, AtomicI8;
+        "16": AtomicU16, AtomicI16;
+        "32": AtomicU32, AtomicI32;
+        "64": AtomicU64, AtomicI64;
+        "ptr": AtomicUsize, AtomicIsize;
+    }
+}
+
+#[cfg(feature = "alloc")]
+mod smart_pointers {
+    use alloc::{
+        borrow::{Cow, ToOwned},
+        boxed::Box,
+        rc::Rc,
+        sync::Arc,
+    };
+    use core::ptr;
+
+    use super::impl_prelude::*;
+
+    macro_rules! forward {
+        ($($ty:ident),*) => {
+            $(
+                impl<T: ?Sized + Debug> Debug for $ty<T> {
+                    fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+                        Debug::fmt(&**self, f)
+                    }
+                }
+
+                impl<T: ?Sized + Display> Display for $ty<T> {
+                    fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+                        Display::fmt(&**self, f)
+                    }
+                }
+
+                impl<T: ?Sized> Pointer for $ty<T> {
+                    fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+                        let ptr: *const T = ptr::addr_of!(**self);
+                        Pointer::fmt(&ptr, f)
+                    }
+                }
+            )*
+        };
+    }
+
+    forward!(Box, Rc, Arc);
+
+    impl<B: ?Sized + ToOwned + Debug> Debug for Cow<'_, B>
+    where
+        B::Owned: Debug,
+    {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            match self {
+                Cow::Borrowed(b) => Debug::fmt(b, f),
+                Cow::Owned(o) => Debug::fmt(o, f),
+            }
+        }
+    }
+
+    impl<B: ?Sized + ToOwned + Display> Display for Cow<'_, B>
+    where
+        B::Owned: Display,
+    {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            match self {
+                Cow::Borrowed(b) => Display::fmt(b, f),
+                Cow::Owned(o) => Display::fmt(o, f),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+mod sync {
+    use std::sync::{Mutex, RwLock, TryLockError};
+
+    use super::{impl_prelude::*, Placeholder};
+
+    impl<T: ?Sized + Debug> Debug for Mutex<T> {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            let mut d = f.debug_struct("Mutex");
+            match self.try_lock() {
+                Ok(guard) => {
+                    d.field("data", &&*guard);
+                }
+                Err(TryLockError::Poisoned(err)) => {
+                    d.field("data", &&**err.get_ref());
+                }
+                Err(TryLockError::WouldBlock) => {
+                    d.field("data", &"<locked>");
+                }
+            }
+            d.field("poisoned", &self.is_poisoned());
+            d.finish_non_exhaustive()
+        }
+    }
+
+    impl<T: ?Sized + Debug> Debug for RwLock<T> {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            let mut d = f.debug_struct("RwLock");
+            match self.try_read() {
+                Ok(guard) => {
+                    d.field("data", &&*guard);
+                }
+                Err(TryLockError::Poisoned(err)) => {
+                    d.field("data", &&**err.get_ref());
+                }
+                Err(TryLockError::WouldBlock) => {
+                    d.field("data", &Placeholder("<locked>"));
+                }
+            }
+            d.field("poisoned", &self.is_poisoned());
+            d.finish_non_exhaustive()
+        }
+    }
+}
+
 // pointers
 mod pointers {
-    use core::ptr;
+    use core::{
+        ops::Deref,
+        pin::Pin,
+        ptr::{self, NonNull},
+    };
 
     use super::impl_prelude::*;
 
@@ -50,6 +456,46 @@ mod pointers {
         }
     }
 
+    impl<T: ?Sized> Debug for NonNull<T> {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            Pointer::fmt(&self.as_ptr(), f)
+        }
+    }
+
+    impl<T: ?Sized> Pointer for NonNull<T> {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            Pointer::fmt(&self.as_ptr(), f)
+        }
+    }
+
+    // core forwards to the private `pointer` field, going through the target is the closest
+    // we can get and prints the same for all pointer types that forward themselves
+
+    impl<P: Deref> Debug for Pin<P>
+    where
+        P::Target: Debug,
+    {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            Debug::fmt(Pin::get_ref(Pin::as_ref(self)), f)
+        }
+    }
+
+    impl<P: Deref> Display for Pin<P>
+    where
+        P::Target: Display,
+    {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            Display::fmt(Pin::get_ref(Pin::as_ref(self)), f)
+        }
+    }
+
+    impl<P: Deref> Pointer for Pin<P> {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            let ptr: *const P::Target = Pin::get_ref(Pin::as_ref(self));
+            Pointer::fmt(&ptr, f)
+        }
+    }
+
     pub(crate) fn pointer_fmt_inner<W: Write, O: FmtOpts>(
         ptr_addr: usize,
         f: &mut Formatter<W, O>,
@@ -92,6 +538,25 @@ mod char {
                 f.pad(self.encode_utf8(&mut [0; 4]))
             }
         }
+
+        fn __mono_fmt_size_hint(&self) -> usize {
+            self.len_utf8()
+        }
+    }
+
+    impl Debug for char {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            f.write_char('\'')?;
+            // `escape_debug` also escapes double quotes, which chars leave alone
+            if *self == '"' {
+                f.write_char('"')?;
+            } else {
+                for c in self.escape_debug() {
+                    f.write_char(c)?;
+                }
+            }
+            f.write_char('\'')
+        }
     }
 }
 
@@ -104,30 +569,84 @@ mod strings {
     #[cfg(feature = "alloc")]
     impl Debug for String {
         fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
-            f.write_char('"')?;
-            f.write_str(self)?;
-            f.write_char('"')
+            <str as Debug>::fmt(self, f)
+        }
+
+        fn __mono_fmt_size_hint(&self) -> usize {
+            <str as Debug>::__mono_fmt_size_hint(self)
         }
     }
 
     #[cfg(feature = "alloc")]
     impl Display for String {
         fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
-            f.write_str(self)
+            Display::fmt(self.as_str(), f)
+        }
+
+        fn __mono_fmt_size_hint(&self) -> usize {
+            self.len()
         }
     }
 
     impl Display for str {
         fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
-            f.write_str(self)
+            f.pad(self)
+        }
+
+        fn __mono_fmt_size_hint(&self) -> usize {
+            self.len()
         }
     }
 
     impl Debug for str {
         fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            fn needs_escape(b: u8) -> bool {
+                !(0x20..=0x7E).contains(&b) || b == b'\\' || b == b'"'
+            }
+
             f.write_char('"')?;
-            f.write_str(self)?;
+
+            // substring we know is printable
+            let mut printable_range = 0..0;
+
+            // the loop here first skips over runs of printable ASCII as a fast path.
+            // other chars (unicode, or ASCII that needs escaping) are then handled per-`char`.
+            let mut rest = self;
+            while !rest.is_empty() {
+                let Some(non_printable_start) =
+                    rest.as_bytes().iter().position(|&b| needs_escape(b))
+                else {
+                    printable_range.end += rest.len();
+                    break;
+                };
+
+                printable_range.end += non_printable_start;
+                rest = &rest[non_printable_start..];
+
+                let mut chars = rest.chars();
+                if let Some(c) = chars.next() {
+                    // `escape_debug` also escapes single quotes, which strings leave alone
+                    let esc = c.escape_debug();
+                    if c != '\'' && esc.len() != 1 {
+                        f.write_str(&self[printable_range.clone()])?;
+                        for c in esc {
+                            f.write_char(c)?;
+                        }
+                        printable_range.start = printable_range.end + c.len_utf8();
+                    }
+                    printable_range.end += c.len_utf8();
+                }
+                rest = chars.as_str();
+            }
+
+            f.write_str(&self[printable_range])?;
+
             f.write_char('"')
         }
+
+        /// The quotes around it, escapes make it longer.
+        fn __mono_fmt_size_hint(&self) -> usize {
+            self.len() + 2
+        }
     }
 }
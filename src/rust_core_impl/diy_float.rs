@@ -0,0 +1,45 @@
+//! Extended precision "soft float", for internal use only.
+
+#![allow(clippy::pedantic)]
+
+/// A custom 64-bit floating point type, representing `f * 2^e`.
+#[derive(Copy, Clone, Debug)]
+pub struct Fp {
+    /// The integer mantissa.
+    pub f: u64,
+    /// The exponent in base 2.
+    pub e: i16,
+}
+
+impl Fp {
+    /// Returns a correctly rounded product of itself and `other`.
+    pub fn mul(self, other: Self) -> Self {
+        let v = u128::from(self.f) * u128::from(other.f);
+        let (lo, hi) = (v as u64, (v >> 64) as u64);
+        let f = hi + (lo >> 63) /* round */;
+        let e = self.e + other.e + 64;
+        Self { f, e }
+    }
+
+    /// Normalizes itself so that the resulting mantissa is at least `2^63`.
+    pub fn normalize(self) -> Self {
+        let lz = self.f.leading_zeros();
+        let f = self.f << lz;
+        let e = self.e - lz as i16;
+        debug_assert!(f >= (1 << 63));
+        Self { f, e }
+    }
+
+    /// Normalizes itself to have the shared exponent.
+    /// It can only decrease the exponent (and thus increase the mantissa).
+    pub fn normalize_to(self, e: i16) -> Self {
+        let edelta = self.e - e;
+        assert!(edelta >= 0);
+        let edelta = edelta as usize;
+        assert_eq!(self.f << edelta >> edelta, self.f);
+        Self {
+            f: self.f << edelta,
+            e,
+        }
+    }
+}
@@ -3,6 +3,10 @@
 #![allow(dead_code)]
 
 mod aggregated;
+mod bignum;
+mod diy_float;
+mod float;
+mod flt2dec;
 mod num;
 
 use crate::{opts::Alignment, Error, FmtOpts, Formatter, Result, Write};
@@ -21,7 +25,7 @@ mod numfmt {
         Copy(&'a [u8]),
     }
 
-    impl<'a> Part<'a> {
+    impl Part<'_> {
         /// Returns the exact byte length of given part.
         pub fn len(&self) -> usize {
             match *self {
@@ -85,7 +89,7 @@ mod numfmt {
         pub parts: &'a [Part<'a>],
     }
 
-    impl<'a> Formatted<'a> {
+    impl Formatted<'_> {
         /// Returns the exact byte length of combined formatted result.
         pub fn len(&self) -> usize {
             let mut len = self.sign.len();
@@ -261,7 +265,7 @@ impl<W: Write, O: FmtOpts> Formatter<W, O> {
         let (pre_pad, post_pad) = match align {
             Alignment::Left => (0, padding),
             Alignment::Right | Alignment::Unknown => (padding, 0),
-            Alignment::Center => (padding / 2, (padding + 1) / 2),
+            Alignment::Center => (padding / 2, padding.div_ceil(2)),
         };
 
         for _ in 0..pre_pad {
@@ -192,6 +192,10 @@ macro_rules! debug {
                     fmt::Display::fmt(self, f)
                 }
             }
+
+            fn __mono_fmt_size_hint(&self) -> usize {
+                fmt::Display::__mono_fmt_size_hint(self)
+            }
         }
     )*};
 }
@@ -200,6 +204,13 @@ debug! {
   u8 u16 u32 u64 u128 usize
 }
 
+/// The length of the longest decimal number of an integer type, with its sign.
+macro_rules! int_size_hint {
+    ($t:ty) => {
+        <$t>::MAX.ilog10() as usize + 1 + usize::from(<$t>::MIN != 0)
+    };
+}
+
 // 2 digit decimal look up table
 static DEC_DIGITS_LUT: &[u8; 200] = b"0001020304050607080910111213141516171819\
       2021222324252627282930313233343536373839\
@@ -287,6 +298,10 @@ macro_rules! impl_Display {
                 };
                 $name(n, is_nonnegative, f)
             }
+
+            fn __mono_fmt_size_hint(&self) -> usize {
+                int_size_hint!($t)
+            }
         })*
     };
 }
@@ -568,6 +583,10 @@ impl fmt::Display for u128 {
     fn fmt<W: Write, O: FmtOpts>(&self, f: &mut fmt::Formatter<W, O>) -> fmt::Result {
         fmt_u128(*self, true, f)
     }
+
+    fn __mono_fmt_size_hint(&self) -> usize {
+        int_size_hint!(u128)
+    }
 }
 
 impl fmt::Display for i128 {
@@ -581,6 +600,10 @@ impl fmt::Display for i128 {
         };
         fmt_u128(n, is_nonnegative, f)
     }
+
+    fn __mono_fmt_size_hint(&self) -> usize {
+        int_size_hint!(i128)
+    }
 }
 
 /// Specialized optimization for u128. Instead of taking two items at a time, it splits
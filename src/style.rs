@@ -0,0 +1,272 @@
+//! Terminal styles, set with `{:(fg=red,bold)}` in format strings or [`Formatter::with_style`].
+//!
+//! Writers get the style with [`Write::write_style`]. Plain text writers drop it, [`Ansi`] turns it
+//! into escape codes.
+//!
+//! A style in a format spec covers the padding too, so a background color fills the whole width:
+//!
+//! ```
+//! use mono_fmt::Ansi;
+//!
+//! let mut out = Ansi::new(String::new());
+//! mono_fmt::write!(out, "{:*^6(bg=blue)}|", "ab").unwrap();
+//! assert_eq!(out.into_inner(), "\x1b[0;44m**ab**\x1b[0m|");
+//! ```
+
+use crate::{opts::WithStyle, FmtOpts, Formatter, Result, Write};
+
+/// A terminal color, `Default` being the one of the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+    Default,
+}
+
+impl Color {
+    /// The SGR code for the foreground, the background one is 10 higher.
+    fn code(self) -> u8 {
+        match self {
+            Self::Black => 30,
+            Self::Red => 31,
+            Self::Green => 32,
+            Self::Yellow => 33,
+            Self::Blue => 34,
+            Self::Magenta => 35,
+            Self::Cyan => 36,
+            Self::White => 37,
+            Self::Default => 39,
+            Self::BrightBlack => 90,
+            Self::BrightRed => 91,
+            Self::BrightGreen => 92,
+            Self::BrightYellow => 93,
+            Self::BrightBlue => 94,
+            Self::BrightMagenta => 95,
+            Self::BrightCyan => 96,
+            Self::BrightWhite => 97,
+        }
+    }
+}
+
+/// A set of text attributes like bold or underlined.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Attributes(u8);
+
+impl Attributes {
+    pub const NONE: Self = Self(0);
+    pub const BOLD: Self = Self(1 << 0);
+    pub const DIM: Self = Self(1 << 1);
+    pub const ITALIC: Self = Self(1 << 2);
+    pub const UNDERLINE: Self = Self(1 << 3);
+    pub const BLINK: Self = Self(1 << 4);
+    pub const REVERSE: Self = Self(1 << 5);
+    pub const HIDDEN: Self = Self(1 << 6);
+    pub const STRIKETHROUGH: Self = Self(1 << 7);
+
+    /// The SGR codes, in the order of the bits.
+    const CODES: [u8; 8] = [1, 2, 3, 4, 5, 7, 8, 9];
+
+    #[must_use]
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    #[must_use]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+/// The colors and attributes of some text. Unset colors are inherited from the surrounding style.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Style {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub attributes: Attributes,
+}
+
+impl Style {
+    /// No style at all, the default.
+    pub const PLAIN: Self = Self {
+        foreground: None,
+        background: None,
+        attributes: Attributes::NONE,
+    };
+
+    #[must_use]
+    pub const fn fg(self, color: Color) -> Self {
+        Self {
+            foreground: Some(color),
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub const fn bg(self, color: Color) -> Self {
+        Self {
+            background: Some(color),
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub const fn with(self, attributes: Attributes) -> Self {
+        Self {
+            attributes: self.attributes.union(attributes),
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub const fn is_plain(&self) -> bool {
+        self.foreground.is_none() && self.background.is_none() && self.attributes.is_empty()
+    }
+
+    /// Layers `other` over `self`, its colors win and the attributes add up.
+    #[must_use]
+    pub const fn patch(self, other: Self) -> Self {
+        Self {
+            foreground: match other.foreground {
+                Some(color) => Some(color),
+                None => self.foreground,
+            },
+            background: match other.background {
+                Some(color) => Some(color),
+                None => self.background,
+            },
+            attributes: self.attributes.union(other.attributes),
+        }
+    }
+}
+
+impl<W: Write, O: FmtOpts> Formatter<W, O> {
+    /// Writes what `write` writes with `style` layered over the current style, then switches
+    /// back to the current one.
+    ///
+    /// ```
+    /// use mono_fmt::{Ansi, Color, FmtOpts, Formatter, Style, Write};
+    ///
+    /// struct Status(bool);
+    ///
+    /// impl mono_fmt::Display for Status {
+    ///     fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> mono_fmt::Result {
+    ///         f.write_str("status: ")?;
+    ///         match self.0 {
+    ///             true => f.with_style(Style::PLAIN.fg(Color::Green), |f| f.write_str("ok")),
+    ///             false => f.with_style(Style::PLAIN.fg(Color::Red), |f| f.write_str("failed")),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(mono_fmt::format!("{}", Status(true)), "status: ok");
+    ///
+    /// let mut out = Ansi::new(String::new());
+    /// mono_fmt::write!(out, "{}", Status(false)).unwrap();
+    /// assert_eq!(out.into_inner(), "status: \x1b[0;31mfailed\x1b[0m");
+    /// ```
+    pub fn with_style(
+        &mut self,
+        style: Style,
+        write: impl FnOnce(&mut Formatter<&mut W, WithStyle<O>>) -> Result,
+    ) -> Result {
+        let current = self.style();
+        let patched = current.patch(style);
+        if patched == current {
+            return write(&mut self.wrap_with(&WithStyle((), patched)));
+        }
+
+        self.buf.write_style(patched)?;
+        write(&mut self.wrap_with(&WithStyle((), patched)))?;
+        self.buf.write_style(current)
+    }
+}
+
+/// Writes styles as ANSI escape codes, for terminals.
+///
+/// ```
+/// use mono_fmt::Ansi;
+///
+/// let mut out = Ansi::new(String::new());
+/// mono_fmt::write!(out, "{:(fg=red,bold)} {}", "error", 1).unwrap();
+/// assert_eq!(out.into_inner(), "\x1b[0;1;31merror\x1b[0m 1");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Ansi<W> {
+    inner: W,
+    current: Style,
+}
+
+impl<W> Ansi<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            current: Style::PLAIN,
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for Ansi<W> {
+    fn write_str(&mut self, str: &str) -> Result {
+        self.inner.write_str(str)
+    }
+
+    fn write_char(&mut self, char: char) -> Result {
+        self.inner.write_char(char)
+    }
+
+    fn write_style(&mut self, style: Style) -> Result {
+        if style == self.current {
+            return Ok(());
+        }
+        self.current = style;
+
+        // always start from a reset, attributes can't be turned off one by one everywhere
+        self.inner.write_str("\x1b[0")?;
+        for (bit, code) in Attributes::CODES.into_iter().enumerate() {
+            if style.attributes.contains(Attributes(1 << bit)) {
+                write_code(&mut self.inner, code)?;
+            }
+        }
+        if let Some(color) = style.foreground {
+            write_code(&mut self.inner, color.code())?;
+        }
+        if let Some(color) = style.background {
+            write_code(&mut self.inner, color.code() + 10)?;
+        }
+        self.inner.write_char('m')
+    }
+}
+
+fn write_code<W: Write>(w: &mut W, code: u8) -> Result {
+    w.write_char(';')?;
+    if code >= 100 {
+        w.write_char('1')?;
+    }
+    if code >= 10 {
+        w.write_char(char::from(b'0' + code / 10 % 10))?;
+    }
+    w.write_char(char::from(b'0' + code % 10))
+}
@@ -1,4 +1,4 @@
-use crate::{Error, Result, Write};
+use crate::{Error, ErrorKind, Result, Style, Write};
 
 impl<W: Write> Write for &mut W {
     fn write_str(&mut self, str: &str) -> Result {
@@ -8,6 +8,20 @@ impl<W: Write> Write for &mut W {
     fn write_char(&mut self, char: char) -> Result {
         <W as Write>::write_char(self, char)
     }
+
+    fn write_style(&mut self, style: Style) -> Result {
+        <W as Write>::write_style(self, style)
+    }
+}
+
+impl Write for core::fmt::Formatter<'_> {
+    fn write_str(&mut self, str: &str) -> Result {
+        <Self as core::fmt::Write>::write_str(self, str).map_err(Error::from)
+    }
+
+    fn write_char(&mut self, char: char) -> Result {
+        <Self as core::fmt::Write>::write_char(self, char).map_err(Error::from)
+    }
 }
 
 /// Write is implemented for `&mut [u8]` by copying into the slice, overwriting
@@ -20,7 +34,7 @@ impl Write for &'_ mut [u8] {
         let data = str.as_bytes();
 
         if data.len() > self.len() {
-            return Err(Error);
+            return Err(Error::new(ErrorKind::Capacity));
         }
 
         let (a, b) = core::mem::take(self).split_at_mut(data.len());
@@ -34,7 +48,7 @@ impl Write for &'_ mut [u8] {
 mod alloc_impls {
     use alloc::{boxed::Box, collections::VecDeque, string::String, vec::Vec};
 
-    use crate::{Result, Write};
+    use crate::{Result, Style, Write};
 
     impl Write for String {
         fn write_str(&mut self, str: &str) -> Result {
@@ -56,6 +70,10 @@ mod alloc_impls {
         fn write_char(&mut self, char: char) -> Result {
             <W as Write>::write_char(self, char)
         }
+
+        fn write_style(&mut self, style: Style) -> Result {
+            <W as Write>::write_style(self, style)
+        }
     }
 
     impl Write for Vec<u8> {
@@ -73,6 +91,9 @@ mod alloc_impls {
     }
 }
 
+#[cfg(feature = "std")]
+pub use std_impls::IoWriter;
+
 #[cfg(feature = "std")]
 mod std_impls {
     use std::{
@@ -81,21 +102,68 @@ mod std_impls {
         net, process,
     };
 
-    use crate::{Result, Write};
+    use crate::{Error, Result, Write};
+
+    /// Writes to any `std::io::Write`, keeping the `std::io::Error` behind a failed write, which
+    /// [`Error`] only has the kind of.
+    ///
+    /// ```
+    /// use mono_fmt::{ErrorKind, IoWriter};
+    ///
+    /// let mut buf = [0; 2];
+    /// let mut out = IoWriter::new(&mut buf[..]);
+    /// let err = mono_fmt::write!(out, "{}", 123).unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::Capacity);
+    /// assert_eq!(out.take_error().unwrap().kind(), std::io::ErrorKind::WriteZero);
+    /// ```
+    #[derive(Debug)]
+    pub struct IoWriter<W> {
+        inner: W,
+        error: Option<io::Error>,
+    }
+
+    impl<W> IoWriter<W> {
+        pub fn new(inner: W) -> Self {
+            Self { inner, error: None }
+        }
+
+        /// Takes the error of the last failed write, if it wasn't taken yet.
+        pub fn take_error(&mut self) -> Option<io::Error> {
+            self.error.take()
+        }
+
+        pub fn get_mut(&mut self) -> &mut W {
+            &mut self.inner
+        }
+
+        pub fn into_inner(self) -> W {
+            self.inner
+        }
+    }
+
+    impl<W: IoWrite> Write for IoWriter<W> {
+        fn write_str(&mut self, str: &str) -> Result {
+            self.inner.write_all(str.as_bytes()).map_err(|io| {
+                let err = Error::of_io(&io);
+                self.error = Some(io);
+                err
+            })
+        }
+    }
 
     macro_rules! impl_io_forward {
         ($($name:ty),* $(,)?) => {
             $(
                 impl Write for $name {
                     fn write_str(&mut self, str: &str) -> Result {
-                        <Self as IoWrite>::write_all(self, str.as_bytes()).map_err(|_| crate::Error)
+                        <Self as IoWrite>::write_all(self, str.as_bytes()).map_err(crate::Error::from)
                     }
 
                     fn write_char(&mut self, char: char) -> Result {
                         let mut buf = [0; 4];
 
                         <Self as IoWrite>::write_all(self, char.encode_utf8(&mut buf).as_bytes())
-                            .map_err(|_| crate::Error)
+                            .map_err(crate::Error::from)
                     }
                 }
             )*
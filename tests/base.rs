@@ -41,7 +41,6 @@ fn dont_move() {
     assert_eq!("owo", string);
 }
 
-
 #[test]
 fn ptr_correct_addr() {
     static STATIC: u8 = 0;
@@ -54,4 +53,206 @@ fn ptr_correct_addr() {
 #[test]
 fn temporaries() {
     let _ = format_args!("{}", { "owo".to_string() });
-}
\ No newline at end of file
+}
+
+#[test]
+fn runtime_width_and_precision() {
+    let width = 6;
+    assert_eq!(format!("{:1$}|", 1, 4), "   1|");
+    assert_eq!(format!("{:>1$}|{}", 7, 3), "  7|3");
+    assert_eq!(format!("{:.*}", 2, 1.23456), "1.23");
+    assert_eq!(format!("{}{:.*}{}", 'a', 1, 0.55, 'b'), "a0.6b");
+    assert_eq!(format!("{:w$.p$}|", 1.5, w = 7, p = 3), "  1.500|");
+    assert_eq!(format!("{:width$}|", 12), "    12|");
+    assert_eq!(format!("{:<0$}|", width), "6     |");
+    assert_eq!(format!("{:^1$.2$}", 0.125, 6, 1), " 0.1  ");
+}
+
+#[test]
+fn fill() {
+    assert_eq!(format!("{:*^9}", 123), "***123***");
+    assert_eq!(format!("{:0>5}", 7), "00007");
+    assert_eq!(format!("{:-<4}|", 1), "1---|");
+    assert_eq!(format!("{:é>4}", 1), "ééé1");
+    assert_eq!(format!("{:🦀^7x}", 255), "🦀🦀ff🦀🦀🦀");
+    assert_eq!(format!("{:_>1$}", 1.5, 6), "___1.5");
+    assert_eq!(format!("{:x<+6}", 1), "+1xxxx");
+}
+
+#[test]
+fn macro_built_format_str() {
+    macro_rules! wrap {
+        ($fmt:expr, $($arg:tt)*) => {
+            format!(concat!("[", $fmt, "]"), $($arg)*)
+        };
+    }
+    macro_rules! forward {
+        ($fmt:expr $(, $arg:expr)*) => {
+            format!($fmt $(, $arg)*)
+        };
+    }
+    macro_rules! forward_literal {
+        ($fmt:literal $(, $arg:expr)*) => {
+            format!($fmt $(, $arg)*)
+        };
+    }
+
+    assert_eq!(format!(concat!("a", "{}"), 1), "a1");
+    assert_eq!(format!(std::concat!("{:", 4, "}|"), 1), "   1|");
+    assert_eq!(format!(concat!('{', "}-", -1, 'x', true), 2), "2--1xtrue");
+    assert_eq!(format!(concat!("{a}", concat!("{}")), 2, a = 1), "12");
+    assert_eq!(format!(stringify!(a {} b), 1), "a 1 b");
+    // spaced like `stringify!` itself, which keeps the spacing of the source
+    assert_eq!(
+        format!(stringify!(a+b, x::y<z>(-1) {}), 1),
+        "a+b, x::y<z>(-1) 1"
+    );
+    assert_eq!(format!(stringify!(a + b, &c)), stringify!(a + b, &c));
+    assert_eq!(wrap!("{:>3}", 5), "[  5]");
+    assert_eq!(wrap!(concat!("{}", "{}"), 1, 2), "[12]");
+    assert_eq!(forward!("{}-{}", 1, 2), "1-2");
+    assert_eq!(forward!(concat!("{}", "!"), 1), "1!");
+    assert_eq!(forward_literal!("{:?}", "x"), "\"x\"");
+
+    let x = 3;
+    assert_eq!(forward!("{x}"), "3");
+}
+
+#[test]
+fn implicit_captures() {
+    let (x, width, prec) = (5, 6, 2);
+    assert_eq!(format!("{x} {x:?} {x:>width$}|"), "5 5      5|");
+    assert_eq!(format!("{:width$.prec$}|", 1.5), "  1.50|");
+    assert_eq!(format!("{x:.prec$} {0:x$}|", 1.25), "5  1.25|");
+    assert_eq!(format!("{:>1$}|{x:width$}", 1, x), "    1|     5");
+    // a named argument takes precedence over a variable of the same name, everywhere
+    assert_eq!(format!("{x} {:x$}|", 1, x = 3), "3   1|");
+}
+
+#[test]
+fn option_aware_impl() {
+    use mono_fmt::{Alignment, FmtOpts, Formatter, Write};
+
+    struct Arrow;
+
+    impl mono_fmt::Display for Arrow {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> mono_fmt::Result {
+            f.write_str(match f.align() {
+                Alignment::Left => "<-",
+                Alignment::Center => "<->",
+                Alignment::Right => "->",
+                Alignment::Unknown => "-",
+            })
+        }
+    }
+
+    assert_eq!(
+        format!("{Arrow:<} {Arrow:^} {Arrow:>} {Arrow}"),
+        "<- <-> -> -"
+    );
+    assert_eq!(format!("{Arrow:*^5}"), "<->");
+}
+
+#[test]
+fn runtime_opts() {
+    use mono_fmt::{Alignment, Debug, Display, Formatter, RuntimeOpts};
+
+    let mut out = String::new();
+    let rows = [("name", 1.5), ("longer name", 22.25)];
+    let width = rows.iter().map(|(name, _)| name.len()).max().unwrap();
+    for (name, value) in rows {
+        let mut f = Formatter::new(&mut out)
+            .with_width(width)
+            .with_align(Alignment::Left);
+        Display::fmt(name, &mut f).unwrap();
+        let mut f = Formatter::new(&mut out)
+            .with_width(8)
+            .with_precision(1)
+            .with_fill('.');
+        Display::fmt(&value, &mut f).unwrap();
+        out.push('\n');
+    }
+    assert_eq!(out, "name       .....1.5\nlonger name....22.2\n");
+
+    let mut out = String::new();
+    let opts = RuntimeOpts {
+        debug_lower_hex: true,
+        alternate: true,
+        ..RuntimeOpts::default()
+    };
+    Debug::fmt(&[255, 16], &mut Formatter::new(&mut out).with_opts(opts)).unwrap();
+    assert_eq!(out, std::format!("{:#x?}", [255, 16]));
+}
+
+#[test]
+fn runtime_opts_by_ref() {
+    use mono_fmt::{Alignment, Display, FmtOpts, Formatter, RuntimeOpts, Write};
+
+    /// Pads the label to the width of the spec, then the value to a fixed width.
+    struct Row(&'static str, u32);
+
+    impl Display for Row {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> mono_fmt::Result {
+            let label = RuntimeOpts {
+                width: f.width(),
+                align: Alignment::Left,
+                ..RuntimeOpts::default()
+            };
+            f.with_runtime_opts(label, |f| Display::fmt(self.0, f))?;
+            let value = RuntimeOpts {
+                width: Some(4),
+                fill: '0',
+                align: Alignment::Right,
+                ..RuntimeOpts::default()
+            };
+            f.with_runtime_opts(value, |f| Display::fmt(&self.1, f))?;
+            f.write_str("|")
+        }
+    }
+
+    assert_eq!(format!("{:6}", Row("id", 7)), "id    0007|");
+    assert_eq!(format!("{}", Row("count", 42)), "count0042|");
+}
+
+#[test]
+fn size_hint() {
+    use mono_fmt::Arguments;
+
+    // the literals, the widest `u8`, the quoted string and the exact `bool`
+    let hint = format_args!("id={} name={:?} ok={}", 42u8, "bob", true).size_hint();
+    assert_eq!(hint, 3 + 3 + 6 + 5 + 4 + 4);
+    // the width is a lower bound, values without an estimate add nothing
+    assert_eq!(format_args!("{:>10}", 'c').size_hint(), 10);
+    assert_eq!(format_args!("{:?}", [1, 2]).size_hint(), 0);
+    assert_eq!(format_args!("{}{}", i128::MIN, u64::MAX).size_hint(), 60);
+
+    let string = format!("id={} name={:?} ok={}", 42u8, "bob", true);
+    assert_eq!(string, "id=42 name=\"bob\" ok=true");
+    assert!(string.capacity() >= hint);
+}
+
+#[test]
+fn size_hint_is_not_on_the_traits() {
+    use mono_fmt::*;
+
+    struct Countdown(u8);
+
+    impl Iterator for Countdown {
+        type Item = u8;
+
+        fn next(&mut self) -> Option<u8> {
+            self.0 = self.0.checked_sub(1)?;
+            Some(self.0)
+        }
+    }
+
+    impl Display for Countdown {
+        fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> Result {
+            Display::fmt(&self.0, f)
+        }
+    }
+
+    // only `Iterator` has a `size_hint` with the formatting traits in scope
+    assert_eq!(Countdown(3).size_hint(), (0, None));
+    assert_eq!(mono_fmt::format!("{}", Countdown(3)), "3");
+}
@@ -0,0 +1,63 @@
+use std::cmp::Ordering;
+
+#[macro_use]
+extern crate mono_fmt;
+
+#[macro_use]
+mod common;
+
+#[test]
+fn bool() {
+    check! {
+        [true, false]:
+        "{}", "{:?}", "{:#?}",
+    }
+}
+
+#[test]
+fn unit() {
+    check! {
+        [()]:
+        "{:?}", "{:#?}",
+    }
+}
+
+#[test]
+fn option_and_result() {
+    check! {
+        [Some(1), None::<u8>, Some(Some("a"))]:
+        "{:?}", "{:#?}", "{:x?}",
+    }
+    check! {
+        [Ok::<u8, ()>(1), Err::<u8, _>("no"), Err::<u8, _>(Some(()))]:
+        "{:?}", "{:#?}",
+    }
+    check! {
+        [Some(255)]:
+        "{:x?}",
+    }
+}
+
+#[test]
+fn ordering() {
+    check! {
+        [Ordering::Less, Ordering::Equal, Ordering::Greater, Some(1.cmp(&2))]:
+        "{:?}",
+    }
+}
+
+#[test]
+fn tuples() {
+    check! {
+        [(1,), (1, "a"), (1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12)]:
+        "{:?}", "{:#?}",
+    }
+    check! {
+        [(true, (), Some(('c', 1.5)))]:
+        "{:#?}",
+    }
+    check! {
+        [(255, [10, 11])]:
+        "{:X?}",
+    }
+}
@@ -0,0 +1,160 @@
+use std::{
+    cell::Cell,
+    fmt,
+    net::{Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+
+#[macro_use]
+extern crate mono_fmt;
+
+use mono_fmt::{Mono, Std};
+
+/// Only implements the `core::fmt` traits.
+struct Celsius(f64);
+
+impl fmt::Display for Celsius {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        std::write!(f, "{}°C", self.0)
+    }
+}
+
+impl fmt::Debug for Celsius {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Celsius").field(&self.0).finish()
+    }
+}
+
+macro_rules! check_std {
+    ($($spec:literal, $value:expr;)*) => {
+        $(
+            assert_eq!(format!($spec, Std(&$value)), std::format!($spec, $value), "{}", $spec);
+        )*
+    };
+}
+
+macro_rules! check_mono {
+    ($($spec:literal, $value:expr;)*) => {
+        $(
+            assert_eq!(std::format!($spec, Mono(&$value)), std::format!($spec, $value), "{}", $spec);
+        )*
+    };
+}
+
+#[test]
+fn std_values() {
+    let addr = Ipv4Addr::new(10, 0, 0, 1);
+    check_std! {
+        "{}", addr;
+        "{:>12}", addr;
+        "{:*^12}", addr;
+        "{:-<4}", addr;
+        "{:?}", SocketAddr::from((addr, 80));
+        "{:?}", Duration::from_millis(1500);
+        "{:.2?}", Duration::from_millis(1500);
+        "{}", Celsius(21.5);
+        "{:#?}", Celsius(-3.0);
+        "{:#x?}", [10, 11];
+        "{:X?}", Some(255);
+        "{:+08.2}", 1.23456;
+        "{:#010b}", 5;
+        "{:#o}", 8;
+        "{:x}", 255;
+        "{:#X}", 255;
+        "{:e}", 1500.0;
+        "{:E}", 0.00015;
+    }
+}
+
+#[test]
+fn std_pads_whole_output() {
+    // unlike `std`, which leaves the padding to the value
+    assert_eq!(format!("{:~>10}", Std(&Celsius(21.5))), "~~~~21.5°C");
+    assert_eq!(format!("{:*^9?}", Std(&[1, 2])), "*[1, 2]**");
+    assert_eq!(format!("{:_<+10?}", Std(&Some(1))), "Some(+1)__");
+    // without an alignment, `core` pads
+    assert_eq!(format!("{:5}", Std(&12)), std::format!("{:5}", 12));
+    assert_eq!(format!("{:~<08}", Std(&-1)), std::format!("{:~<08}", -1));
+}
+
+#[test]
+fn std_keeps_max_char() {
+    /// Writes the last `char`, which must come out unchanged.
+    struct Max;
+
+    impl fmt::Display for Max {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("\u{10FFFF}")
+        }
+    }
+
+    assert_eq!(format!("{}", Std(&Max)), "\u{10FFFF}");
+    assert_eq!(format!("{:*^5}", Std(&Max)), "**\u{10FFFF}**");
+    assert_eq!(format!("{:>3}", Std(&Max)), "  \u{10FFFF}");
+}
+
+#[test]
+fn std_runtime_options() {
+    let width = 9;
+    let precision = 1;
+    assert_eq!(
+        format!("{:>width$.precision$}", Std(&2.25)),
+        std::format!("{:>width$.precision$}", 2.25),
+    );
+    assert_eq!(format!("{:_^0}", Std(&1)), "1");
+}
+
+#[test]
+fn std_formats_once() {
+    struct Counted<'a>(&'a Cell<usize>);
+
+    impl fmt::Display for Counted<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            self.0.set(self.0.get() + 1);
+            f.pad("abc")
+        }
+    }
+
+    let calls = Cell::new(0);
+    assert_eq!(format!("{}", Std(&Counted(&calls))), "abc");
+    assert_eq!(calls.get(), 1);
+    assert_eq!(format!("{:>6}", Std(&Counted(&calls))), "   abc");
+    assert_eq!(calls.get(), 2);
+    assert_eq!(format!("{:*^7}", Std(&Counted(&calls))), "**abc**");
+    assert_eq!(calls.get(), 3);
+}
+
+#[test]
+fn mono_values() {
+    mod mono {
+        #[derive(mono_fmt::Debug)]
+        pub struct Pair(pub i32, pub &'static str);
+    }
+
+    mod std_fmt {
+        #[derive(std::fmt::Debug)]
+        #[allow(dead_code)]
+        pub struct Pair(pub i32, pub &'static str);
+    }
+
+    check_mono! {
+        "{}", 42;
+        "{:+08}", 5u32;
+        "{:*<6}", 7;
+        "{:#x}", 255;
+        "{:#b}", 5;
+        "{:.3}", 1.0;
+        "{:>10.1e}", 1234.5;
+        "{:?}", [1, 2, 3];
+    }
+    assert_eq!(
+        std::format!("{:#?}", Mono(&mono::Pair(1, "a"))),
+        std::format!("{:#?}", std_fmt::Pair(1, "a")),
+    );
+}
+
+#[test]
+fn round_trip() {
+    assert_eq!(format!("{:>8}", Std(&Mono(&12))), "      12");
+    assert_eq!(std::format!("{:^7}", Mono(&Std(&12))), "  12   ");
+}
@@ -0,0 +1,46 @@
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque};
+
+#[macro_use]
+extern crate mono_fmt;
+
+#[macro_use]
+mod common;
+
+#[test]
+fn lists() {
+    check! {
+        [
+            Vec::<u8>::new(),
+            vec![1, 20, 300],
+            vec![vec!["a"], vec![]],
+            VecDeque::from([1, 2, 3]),
+            LinkedList::from([Some(1), None]),
+            BinaryHeap::from([5]),
+        ]:
+        "{:?}", "{:#?}", "{:x?}",
+    }
+}
+
+#[test]
+fn maps() {
+    // built once, as every `HashMap` iterates in its own order
+    let hash_map = HashMap::from([(1, 'a'), (2, 'b'), (3, 'c')]);
+    check! {
+        [
+            BTreeMap::<u8, u8>::new(),
+            BTreeMap::from([(1, "one"), (2, "two")]),
+            BTreeMap::from([("nested", BTreeMap::from([(255, vec![1])]))]),
+            hash_map,
+        ]:
+        "{:?}", "{:#?}", "{:x?}",
+    }
+}
+
+#[test]
+fn sets() {
+    let hash_set = HashSet::from(["a", "b"]);
+    check! {
+        [BTreeSet::from([3, 1, 2]), hash_set, BTreeSet::<()>::new()]:
+        "{:?}", "{:#?}", "{:x?}",
+    }
+}
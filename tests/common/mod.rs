@@ -0,0 +1,21 @@
+//! Helpers shared by the integration tests.
+
+/// Checks every spec against every value, comparing `format!` with `std::format!`.
+macro_rules! check {
+    ($values:tt: $($spec:literal),* $(,)?) => {
+        $(
+            check!(@spec $spec $values);
+        )*
+    };
+    (@spec $spec:literal [$($value:expr),* $(,)?]) => {
+        $(
+            assert_eq!(
+                format!($spec, $value),
+                std::format!($spec, $value),
+                "{:?} with {:?}",
+                $spec,
+                $value,
+            );
+        )*
+    };
+}
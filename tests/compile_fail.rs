@@ -0,0 +1,6 @@
+//! The errors of the macros as users see them, with their spans.
+
+#[test]
+fn compile_fail() {
+    trybuild::TestCases::new().compile_fail("tests/compile_fail/*.rs");
+}
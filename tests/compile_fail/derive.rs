@@ -0,0 +1,15 @@
+#[derive(mono_fmt::Debug)]
+#[debug(skip)]
+struct Container(u8);
+
+#[derive(mono_fmt::Debug)]
+struct Field(#[debug(hide)] u8);
+
+#[derive(mono_fmt::Display)]
+struct Missing(u8);
+
+#[derive(mono_fmt::Display)]
+#[display("{} {}")]
+struct TooFew(u8);
+
+fn main() {}
@@ -0,0 +1,6 @@
+fn main() {
+    let _ = mono_fmt::format!("{:q}", 1);
+    let _ = mono_fmt::format!("a {", 1);
+    let _ = mono_fmt::format!("{:(fg=purple)}", 1);
+    let _ = mono_fmt::format!(concat!("{}", b'a'), 1);
+}
@@ -0,0 +1,4 @@
+fn main() {
+    let _ = mono_fmt::format!("{5}", 1, 2);
+    let _ = mono_fmt::format!("{} {}", 1);
+}
@@ -0,0 +1,103 @@
+//! Width, fill, alignment and precision for every supported type, compared against std.
+
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    num::{NonZeroU8, Wrapping},
+    pin::Pin,
+    rc::Rc,
+    sync::Arc,
+};
+
+#[macro_use]
+extern crate mono_fmt;
+
+#[macro_use]
+mod common;
+
+/// Checks the padding specs for Display.
+macro_rules! display {
+    ($($value:expr),* $(,)?) => {
+        check! {
+            [$($value),*]:
+            "{}", "{:8}", "{:<8}", "{:^8}", "{:>8}", "{:*<8}", "{:-^9}", "{:é>8}", "{:🦀^6}",
+            "{:.2}", "{:8.2}", "{:^8.1}", "{:_>8.0}", "{:.10}", "{:2}", "{:0}", "{:<1}",
+        }
+    };
+}
+
+/// Checks the padding specs for Debug.
+macro_rules! debug {
+    ($($value:expr),* $(,)?) => {
+        check! {
+            [$($value),*]:
+            "{:?}", "{:8?}", "{:<8?}", "{:^8?}", "{:*>8?}", "{:.1?}", "{:#?}", "{:#10?}",
+        }
+    };
+}
+
+#[test]
+fn strings() {
+    let string = String::from("héllo wörld");
+    display!("", "abc", "héllo wörld", "🦀🦀🦀", "tab\there");
+    display!(&string, string.as_str());
+    display!(
+        Cow::<str>::Borrowed("cow"),
+        Box::<str>::from("box"),
+        Rc::<str>::from("rc"),
+        Arc::<str>::from("arc"),
+    );
+    debug!("", "abc", "quote\"d", "new\nline", &string);
+}
+
+#[test]
+fn chars() {
+    display!('a', 'ß', '🦀', ' ');
+    debug!('a', '\'', '\n', '🦀');
+}
+
+#[test]
+fn bools_and_unit() {
+    display!(true, false);
+    debug!(true, false, ());
+}
+
+#[test]
+fn numbers() {
+    display!(0, 42, -7, u64::MAX, i128::MIN, 1.5, -0.25, 1e21, f32::NAN, -f64::INFINITY);
+    debug!(0, -7, 1.5, 1e-7);
+    display!(Wrapping(300), NonZeroU8::new(9).unwrap());
+    check! {
+        [0, -5, 255]:
+        "{:+8}", "{:08}", "{:<+8}", "{:^#8x}", "{:*>#10b}", "{:#010X}", "{:+.3e}",
+    }
+    check! {
+        [0.5, -12.375, 1e300]:
+        "{:+8.2}", "{:08.3}", "{:<+10.1}", "{:^12e}", "{:*>12.2E}", "{:+010.0}",
+    }
+}
+
+#[test]
+fn wrappers() {
+    let cell = RefCell::new("in cell");
+    display!(cell.borrow(), Pin::new(&"pinned"), Box::new(-3), Rc::new('r'));
+    debug!(Some("some"), None::<u8>, Ok::<_, ()>(1.5), Box::new("boxed"));
+}
+
+#[test]
+fn runtime_counts() {
+    for width in [0, 1, 5, 12] {
+        for precision in [0, 1, 3, 20] {
+            for value in ["", "abc", "héllo"] {
+                assert_eq!(
+                    format!("{:>width$.precision$}|{:^1$}", value, width),
+                    std::format!("{:>width$.precision$}|{:^1$}", value, width),
+                );
+            }
+            assert_eq!(
+                format!("{:*<width$.precision$}", 2.5),
+                std::format!("{:*<width$.precision$}", 2.5),
+            );
+        }
+    }
+}
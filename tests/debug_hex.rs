@@ -0,0 +1,143 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    num::{NonZeroI64, NonZeroU8, Saturating, Wrapping},
+    rc::Rc,
+    sync::{
+        atomic::{AtomicI32, AtomicU64, AtomicUsize},
+        Mutex,
+    },
+};
+
+#[macro_use]
+extern crate mono_fmt;
+
+#[macro_use]
+mod common;
+
+mod mono {
+    #[derive(mono_fmt::Debug)]
+    pub struct Point {
+        pub x: i32,
+        pub y: u8,
+    }
+
+    #[derive(mono_fmt::Debug)]
+    pub struct Wrapper(pub Vec<Point>, pub Option<u16>);
+
+    #[derive(mono_fmt::Debug)]
+    pub enum Shape {
+        Dot(Point),
+        Line { from: Point, to: Point },
+    }
+}
+
+mod std_fmt {
+    #[derive(std::fmt::Debug)]
+    #[allow(dead_code)]
+    pub struct Point {
+        pub x: i32,
+        pub y: u8,
+    }
+
+    #[derive(std::fmt::Debug)]
+    #[allow(dead_code)]
+    pub struct Wrapper(pub Vec<Point>, pub Option<u16>);
+
+    #[derive(std::fmt::Debug)]
+    #[allow(dead_code)]
+    pub enum Shape {
+        Dot(Point),
+        Line { from: Point, to: Point },
+    }
+}
+
+#[test]
+fn integers() {
+    check! {
+        [
+            0u8,
+            255u8,
+            -1i8,
+            i16::MIN,
+            u32::MAX,
+            -1i64,
+            u128::MAX,
+            i128::MIN,
+            usize::MAX,
+            isize::MIN,
+        ]:
+        "{:x?}", "{:X?}", "{:#x?}", "{:04X?}", "{:#06x?}",
+    }
+    check! {
+        [
+            NonZeroU8::new(200).unwrap(),
+            NonZeroI64::new(-16).unwrap(),
+            Wrapping(300u16),
+            Saturating(-2i32),
+            AtomicUsize::new(4096),
+            AtomicI32::new(-7),
+            AtomicU64::new(u64::MAX),
+        ]:
+        "{:x?}", "{:X?}", "{:#x?}", "{:04X?}", "{:#06x?}",
+    }
+}
+
+#[test]
+fn nested_collections() {
+    check! {
+        [
+            [10u8, 255],
+            vec![vec![10i64, -1], vec![]],
+            (1u16, Some(255usize), Ok::<_, ()>(-3i8)),
+            VecDeque::from([[1, 2], [3, 4]]),
+            BTreeMap::from([(10, vec![11, 12]), (13, vec![])]),
+            BTreeSet::from([Some(Wrapping(14)), None]),
+            Rc::new(RefCell::new(vec![Cell::new(15)])),
+            [Some(NonZeroU8::new(17).unwrap())],
+        ]:
+        "{:x?}", "{:X?}", "{:#x?}", "{:04X?}", "{:#06x?}",
+    }
+
+    let mutex = Mutex::new(Box::new([16u64]));
+    assert_eq!(format!("{:x?}", mutex), std::format!("{:x?}", mutex));
+    assert_eq!(format!("{:#X?}", mutex), std::format!("{:#X?}", mutex));
+}
+
+#[test]
+fn derived() {
+    let point = |x, y| (mono::Point { x, y }, std_fmt::Point { x, y });
+    let ((a, std_a), (b, std_b)) = (point(-1, 255), point(16, 0));
+
+    let mono = mono::Wrapper(vec![a, b], Some(4096));
+    let std = std_fmt::Wrapper(vec![std_a, std_b], Some(4096));
+    for (mono, std) in [
+        (format!("{:x?}", mono), std::format!("{:x?}", std)),
+        (format!("{:#X?}", mono), std::format!("{:#X?}", std)),
+    ] {
+        assert_eq!(mono, std);
+    }
+
+    let ((from, std_from), (to, std_to)) = (point(10, 11), point(12, 13));
+    let mono = [
+        mono::Shape::Dot(mono::Point { x: 255, y: 255 }),
+        mono::Shape::Line { from, to },
+    ];
+    let std = [
+        std_fmt::Shape::Dot(std_fmt::Point { x: 255, y: 255 }),
+        std_fmt::Shape::Line {
+            from: std_from,
+            to: std_to,
+        },
+    ];
+    assert_eq!(format!("{:x?}", mono), std::format!("{:x?}", std));
+    assert_eq!(format!("{:#X?}", mono), std::format!("{:#X?}", std));
+}
+
+#[test]
+fn sign_minus() {
+    // `-` is accepted and, like in std, changes nothing
+    assert_eq!(format!("{:-}", -5), std::format!("{:-}", -5));
+    assert_eq!(format!("{:-x?}", [-5]), std::format!("{:-x?}", [-5]));
+    assert_eq!(format!("{:-?}", 1.5), std::format!("{:-?}", 1.5));
+}
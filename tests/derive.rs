@@ -0,0 +1,219 @@
+#![allow(dead_code)]
+
+#[macro_use]
+extern crate mono_fmt;
+
+use mono_fmt::Debug;
+
+#[derive(Debug)]
+struct Unit;
+
+#[derive(Debug)]
+struct Tuple(u8, &'static str);
+
+#[derive(Debug)]
+struct Named {
+    a: u32,
+    b: &'static str,
+}
+
+#[derive(Debug)]
+struct Generic<T> {
+    inner: T,
+}
+
+#[derive(Debug)]
+struct Skipped {
+    shown: u8,
+    #[debug(skip)]
+    hidden: NotDebug,
+}
+
+#[derive(Debug)]
+struct SkippedTuple(#[debug(skip)] NotDebug, u8);
+
+struct NotDebug;
+
+#[derive(Debug)]
+enum Enum {
+    Unit,
+    Tuple(u8),
+    Named { r#type: u8, other: &'static str },
+}
+
+#[derive(Debug)]
+enum Never {}
+
+#[test]
+fn unit() {
+    assert_eq!(format!("{:?}", Unit), "Unit");
+    assert_eq!(format!("{:#?}", Unit), "Unit");
+}
+
+#[test]
+fn tuple() {
+    assert_eq!(format!("{:?}", Tuple(1, "a")), r#"Tuple(1, "a")"#);
+    assert_eq!(
+        format!("{:#?}", Tuple(1, "a")),
+        "Tuple(\n    1,\n    \"a\",\n)"
+    );
+}
+
+#[test]
+fn named() {
+    let named = Named { a: 1, b: "b" };
+    assert_eq!(format!("{:?}", named), r#"Named { a: 1, b: "b" }"#);
+    assert_eq!(
+        format!("{:#?}", named),
+        "Named {\n    a: 1,\n    b: \"b\",\n}"
+    );
+}
+
+#[test]
+fn generic() {
+    let generic = Generic {
+        inner: Generic { inner: [1u8, 2] },
+    };
+    assert_eq!(
+        format!("{:?}", generic),
+        "Generic { inner: Generic { inner: [1, 2] } }"
+    );
+}
+
+#[test]
+fn skip() {
+    let skipped = Skipped {
+        shown: 1,
+        hidden: NotDebug,
+    };
+    assert_eq!(format!("{:?}", skipped), "Skipped { shown: 1 }");
+    assert_eq!(
+        format!("{:?}", SkippedTuple(NotDebug, 2)),
+        "SkippedTuple(2)"
+    );
+}
+
+#[test]
+fn enums() {
+    assert_eq!(format!("{:?}", Enum::Unit), "Unit");
+    assert_eq!(format!("{:?}", Enum::Tuple(5)), "Tuple(5)");
+    assert_eq!(
+        format!(
+            "{:?}",
+            Enum::Named {
+                r#type: 0,
+                other: "o"
+            }
+        ),
+        r#"Named { type: 0, other: "o" }"#
+    );
+}
+
+#[test]
+fn forwards_options() {
+    assert_eq!(format!("{:x?}", Tuple(255, "a")), r#"Tuple(ff, "a")"#);
+}
+
+mod display {
+    use mono_fmt::Display;
+
+    #[derive(Display)]
+    #[display("{name} at {addr:#x}")]
+    struct Named {
+        name: &'static str,
+        addr: u32,
+    }
+
+    #[derive(Display)]
+    #[display("({}, {1:>4})")]
+    struct Tuple(u8, u8);
+
+    #[derive(Display)]
+    #[display("{inner:?} and {extra}", extra = 5)]
+    struct Generic<T> {
+        inner: T,
+    }
+
+    #[derive(Display)]
+    #[display("{value:>width$}|{:.*}", 1, 2.25)]
+    struct RuntimeWidth {
+        value: u8,
+        width: usize,
+    }
+
+    #[derive(Display)]
+    #[display(concat!("<", "{}", ">"))]
+    struct Concat(u8);
+
+    #[derive(Display)]
+    enum Enum {
+        Unit,
+        #[display("renamed")]
+        Renamed,
+        #[display("tuple {}")]
+        Tuple(u8),
+        #[display("named {a}")]
+        Named {
+            a: u8,
+            b: u8,
+        },
+    }
+
+    #[test]
+    fn named() {
+        let named = Named {
+            name: "main",
+            addr: 255,
+        };
+        assert_eq!(format!("{}", named), "main at 0xff");
+    }
+
+    #[test]
+    fn tuple() {
+        assert_eq!(format!("{}", Tuple(1, 2)), "(1,    2)");
+    }
+
+    #[test]
+    fn generic() {
+        assert_eq!(format!("{}", Generic { inner: "a" }), r#""a" and 5"#);
+    }
+
+    #[test]
+    fn enums() {
+        assert_eq!(format!("{}", Enum::Unit), "Unit");
+        assert_eq!(format!("{}", Enum::Renamed), "renamed");
+        assert_eq!(format!("{}", Enum::Tuple(3)), "tuple 3");
+        assert_eq!(format!("{}", Enum::Named { a: 1, b: 2 }), "named 1");
+    }
+
+    #[test]
+    fn runtime_width() {
+        let runtime = RuntimeWidth { value: 1, width: 3 };
+        assert_eq!(format!("{}", runtime), "  1|2.2");
+    }
+
+    #[test]
+    fn concat() {
+        assert_eq!(format!("{}", Concat(7)), "<7>");
+    }
+
+    #[test]
+    fn ignores_outer_options() {
+        assert_eq!(format!("{:>10}", Tuple(1, 2)), "(1,    2)");
+    }
+}
+
+mod crate_path {
+    use mono_fmt as renamed;
+
+    #[derive(mono_fmt::Debug, mono_fmt::Display)]
+    #[mono_fmt(crate = renamed)]
+    #[display("<{}>")]
+    struct Renamed(u8);
+
+    #[test]
+    fn through_reexport() {
+        assert_eq!(format!("{:?}", Renamed(1)), "Renamed(1)");
+        assert_eq!(format!("{}", Renamed(1)), "<1>");
+    }
+}
@@ -0,0 +1,148 @@
+#![allow(clippy::excessive_precision)]
+
+#[macro_use]
+extern crate mono_fmt;
+
+#[macro_use]
+mod common;
+
+fn is_exponential(s: &str) -> bool {
+    s.contains("e")
+}
+
+#[test]
+fn test_format_f64() {
+    assert_eq!("1", format!("{:.0}", 1.0f64));
+    assert_eq!("9", format!("{:.0}", 9.4f64));
+    assert_eq!("10", format!("{:.0}", 9.9f64));
+    assert_eq!("9.8", format!("{:.1}", 9.849f64));
+    assert_eq!("9.9", format!("{:.1}", 9.851f64));
+    assert_eq!("0", format!("{:.0}", 0.5f64));
+    assert_eq!("1.23456789e6", format!("{:e}", 1234567.89f64));
+    assert_eq!("1.23456789e3", format!("{:e}", 1234.56789f64));
+    assert_eq!("1.23456789E6", format!("{:E}", 1234567.89f64));
+    assert_eq!("1.23456789E3", format!("{:E}", 1234.56789f64));
+    assert_eq!("0.0", format!("{:?}", 0.0f64));
+    assert_eq!("1.01", format!("{:?}", 1.01f64));
+
+    let high_cutoff = 1e16_f64;
+    assert_eq!("1e16", format!("{:?}", high_cutoff));
+    assert_eq!("-1e16", format!("{:?}", -high_cutoff));
+    let below_high_cutoff = high_cutoff * (1.0 - 2.0 * f64::EPSILON);
+    assert!(!is_exponential(&format!("{:?}", below_high_cutoff)));
+    assert_eq!("-3.0", format!("{:?}", -3f64));
+    assert_eq!("0.0001", format!("{:?}", 0.0001f64));
+    assert_eq!("9e-5", format!("{:?}", 0.00009f64));
+    assert_eq!("1234567.9", format!("{:.1?}", 1234567.89f64));
+    assert_eq!("1234.6", format!("{:.1?}", 1234.56789f64));
+}
+
+#[test]
+fn test_format_f32() {
+    assert_eq!("1", format!("{:.0}", 1.0f32));
+    assert_eq!("9", format!("{:.0}", 9.4f32));
+    assert_eq!("10", format!("{:.0}", 9.9f32));
+    assert_eq!("9.8", format!("{:.1}", 9.849f32));
+    assert_eq!("9.9", format!("{:.1}", 9.851f32));
+    assert_eq!("0", format!("{:.0}", 0.5f32));
+    assert_eq!("1.2345679e6", format!("{:e}", 1234567.89f32));
+    assert_eq!("1.2345679e3", format!("{:e}", 1234.56789f32));
+    assert_eq!("1.2345679E6", format!("{:E}", 1234567.89f32));
+    assert_eq!("1.2345679E3", format!("{:E}", 1234.56789f32));
+    assert_eq!("0.0", format!("{:?}", 0.0f32));
+    assert_eq!("1.01", format!("{:?}", 1.01f32));
+    assert_eq!("0.1", format!("{}", 0.1f32));
+
+    let high_cutoff = 1e16_f32;
+    assert_eq!("1e16", format!("{:?}", high_cutoff));
+    assert_eq!("-1e16", format!("{:?}", -high_cutoff));
+    let below_high_cutoff = high_cutoff * (1.0 - 2.0 * f32::EPSILON);
+    assert!(!is_exponential(&format!("{:?}", below_high_cutoff)));
+    assert_eq!("-3.0", format!("{:?}", -3f32));
+    assert_eq!("0.0001", format!("{:?}", 0.0001f32));
+    assert_eq!("9e-5", format!("{:?}", 0.00009f32));
+    assert_eq!("1234567.9", format!("{:.1?}", 1234567.89f32));
+    assert_eq!("1234.6", format!("{:.1?}", 1234.56789f32));
+}
+
+#[test]
+fn test_format_special() {
+    assert_eq!("NaN", format!("{}", f64::NAN));
+    assert_eq!("NaN", format!("{:+}", f64::NAN));
+    assert_eq!("NaN", format!("{:?}", -f64::NAN));
+    assert_eq!("inf", format!("{}", f64::INFINITY));
+    assert_eq!("-inf", format!("{}", f64::NEG_INFINITY));
+    assert_eq!("+inf", format!("{:+}", f64::INFINITY));
+    assert_eq!("inf", format!("{:e}", f32::INFINITY));
+    assert_eq!("-0", format!("{}", -0.0f64));
+    assert_eq!("-0.0", format!("{:?}", -0.0f64));
+    assert_eq!("+0", format!("{:+}", 0.0f64));
+    assert_eq!("-0e0", format!("{:e}", -0.0f32));
+    assert_eq!("0.000", format!("{:.3}", 0.0f64));
+}
+
+#[test]
+fn test_format_float_flags() {
+    assert_eq!("     1.5", format!("{:8}", 1.5f64));
+    assert_eq!("1.5     ", format!("{:<8}", 1.5f64));
+    assert_eq!("  1.5   ", format!("{:^8}", 1.5f64));
+    assert_eq!("-00001.5", format!("{:08}", -1.5f64));
+    assert_eq!("+001.500", format!("{:+08.3}", 1.5f64));
+    assert_eq!("  +1.5e0", format!("{:+8e}", 1.5f64));
+    assert_eq!("00000inf", format!("{:08}", f64::INFINITY));
+}
+
+#[test]
+fn test_format_float_matches_std() {
+    let values = [
+        0.0,
+        -0.0,
+        1.0,
+        -1.0,
+        0.1,
+        0.3,
+        1.0 / 3.0,
+        2.0 / 3.0,
+        123.456,
+        -9999.99,
+        1e15,
+        1e16,
+        1e17,
+        1e-4,
+        1e-5,
+        1e-7,
+        1e100,
+        1.7976931348623157e308,
+        5e-324,
+        2.2250738585072014e-308,
+        core::f64::consts::PI,
+        core::f64::consts::E,
+        f64::MAX,
+        f64::MIN,
+        f64::MIN_POSITIVE,
+        f64::EPSILON,
+        f64::NAN,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+    ];
+
+    for value in values {
+        let single = value as f32;
+        check! {
+            [value, single]:
+            "{}", "{:?}", "{:e}", "{:E}", "{:.0}", "{:.3}", "{:.20}", "{:.0e}", "{:.5e}",
+            "{:.2?}", "{:+}", "{:+e}", "{:12}", "{:<12}", "{:^12}", "{:012}", "{:+012.2}",
+            "{:>15e}",
+        }
+    }
+}
+
+#[test]
+fn test_format_float_huge_precision() {
+    assert_eq!(format!("{:.1000}", 1.0f64), std::format!("{:.1000}", 1.0f64));
+    assert_eq!(
+        format!("{:.1100}", 5e-324f64),
+        std::format!("{:.1100}", 5e-324f64)
+    );
+    assert_eq!(format!("{:.800e}", 0.1f64), std::format!("{:.800e}", 0.1f64));
+}
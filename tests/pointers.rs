@@ -0,0 +1,92 @@
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    pin::Pin,
+    ptr::NonNull,
+    rc::Rc,
+    sync::{Arc, Mutex, RwLock},
+};
+
+#[macro_use]
+extern crate mono_fmt;
+
+#[macro_use]
+mod common;
+
+#[test]
+fn smart_pointers() {
+    check! {
+        [Box::new("a"), Cow::<str>::Borrowed("b"), Cow::<str>::Owned("o".to_owned())]:
+        "{}", "{:?}",
+    }
+    check! {
+        [Box::new(1), Arc::new(2.5), Pin::new(Box::new(3)), Pin::new(&4)]:
+        "{}", "{:?}",
+    }
+    check! {
+        [Rc::new(Some(1)), Box::<[u8]>::from([1, 2])]:
+        "{:?}", "{:#?}",
+    }
+}
+
+#[test]
+fn pointer_addresses() {
+    let boxed = Box::new(1);
+    assert_eq!(format!("{:p}", boxed), std::format!("{:p}", boxed));
+    let rc = Rc::new(1);
+    assert_eq!(format!("{:p}", rc), std::format!("{:p}", rc));
+    let arc = Arc::new(1);
+    assert_eq!(format!("{:#p}", arc), std::format!("{:#p}", arc));
+    let pinned = Pin::new(&boxed);
+    assert_eq!(format!("{:p}", pinned), std::format!("{:p}", pinned));
+    let non_null = NonNull::from(&*boxed);
+    assert_eq!(format!("{:p}", non_null), std::format!("{:p}", non_null));
+    assert_eq!(format!("{:?}", non_null), std::format!("{:?}", non_null));
+}
+
+#[test]
+fn cells() {
+    let cell = RefCell::new(vec![1]);
+    check! {
+        [Cell::new(5), cell]:
+        "{:?}", "{:#?}",
+    }
+    {
+        let borrow = cell.borrow();
+        check! {
+            [cell, borrow]:
+            "{:?}",
+        }
+    }
+    let _borrow = cell.borrow_mut();
+    check! {
+        [cell]:
+        "{:?}", "{:#?}",
+    }
+}
+
+#[test]
+fn sync() {
+    let mutex = Mutex::new(1);
+    let rw_lock = RwLock::new("a");
+    check! {
+        [mutex, rw_lock]:
+        "{:?}", "{:#?}",
+    }
+    {
+        let _guard = mutex.lock().unwrap();
+        let _write = rw_lock.write().unwrap();
+        check! {
+            [mutex, rw_lock]:
+            "{:?}",
+        }
+    }
+    let _ = std::panic::catch_unwind(|| {
+        let _guard = mutex.lock().unwrap();
+        panic!("poison");
+    });
+    check! {
+        [mutex]:
+        "{:?}",
+    }
+}
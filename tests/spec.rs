@@ -0,0 +1,92 @@
+//! Runs format specs through both `std::format!` and `mono_fmt::format!` and compares the output.
+
+#[macro_use]
+extern crate mono_fmt;
+
+#[macro_use]
+mod common;
+
+#[test]
+fn integers() {
+    check! {
+        [0, 1, -1, 42, -1234, i32::MAX, i32::MIN]:
+        "{}", "{:5}", "{:<5}", "{:^5}", "{:>5}", "{:*^7}", "{:0^7}", "{:<<6}", "{:}>4}", "{:{<4}",
+        "{:+}", "{:-}", "{:+5}", "{:-5}", "{:05}", "{:+05}", "{:<05}", "{:^+07}", "{:0}", "{:00}",
+        "{:#x}", "{:#010x}", "{:#b}", "{:o}", "{:#o}", "{:X}", "{:#X}", "{:x}", "{:08b}",
+        "{:?}", "{:5?}", "{:#?}", "{:x?}", "{:#X?}", "{:08?}", "{:+?}",
+        "{:<}", "{:>}", "{:^}", "{:.}", "{:5.}", "{:#}",
+    }
+    check! {
+        [0u8, 7, 255]:
+        "{}", "{:3}", "{:03}", "{:#04x}", "{:#b}", "{:#o}", "{:X?}", "{:_>5}",
+    }
+}
+
+#[test]
+fn floats() {
+    check! {
+        [0.0, -0.0, 1.0, 1.5, -2.25, 0.1, 1e10, 1.5e-7, f64::MAX, f64::INFINITY, f64::NAN]:
+        "{}", "{:8}", "{:<8}", "{:^8}", "{:*>8}", "{:+}", "{:08}", "{:+08}", "{:.0}", "{:.3}",
+        "{:8.2}", "{:+.1}", "{:08.2}", "{:e}", "{:E}", "{:.2e}", "{:12.3E}", "{:?}", "{:.1?}",
+        "{:#?}", "{:010?}", "{:.}",
+    }
+    check! {
+        [0.5f32, -3.75f32, 16_777_216.0f32]:
+        "{}", "{:.2}", "{:e}", "{:?}", "{:+010.3}",
+    }
+}
+
+#[test]
+fn chars_and_bools() {
+    check! {
+        ['a', 'ß', '\n', '\'', '"']:
+        "{}", "{:3}", "{:^5}", "{:*<3}", "{:?}",
+    }
+    check! {
+        [true, false]:
+        "{}", "{:6}", "{:>6}", "{:^7}", "{:?}", "{:.2}", "{ }", "{:<}",
+    }
+}
+
+#[test]
+fn whitespace() {
+    check! {
+        [5, -5]:
+        "{ }", "{0 }", "{0\t}", "{0 :}", "{ :5}", "{:5 }", "{:x }", "{:? }", "{: }", "{:\n}",
+        "{: >4}", "{:.2 }",
+    }
+    let a = 3;
+    assert_eq!(format!("{a }"), std::format!("{a }"));
+    assert_eq!(format!("{a :>3 }"), std::format!("{a :>3 }"));
+}
+
+#[test]
+fn counts() {
+    let (width, precision, value) = (9, 2, 1.23456);
+    macro_rules! check_counts {
+        ($($spec:literal),* $(,)?) => {
+            $(
+                assert_eq!(
+                    format!($spec, width, precision, value),
+                    std::format!($spec, width, precision, value),
+                    "{:?}",
+                    $spec,
+                );
+            )*
+        };
+    }
+    check_counts! {
+        "{2:0$.1$}", "{2:>0$.1$}", "{2:00$.1$}", "{2:+00$.1$}", "{2:*^0$.1$}", "{2:0$.1$e}",
+        "{2:.1$} {0}", "{:.*} {}", "{2:0$} {1:0$}", "{:00$} {} {}",
+    }
+    assert_eq!(
+        format!("{:width$.precision$}", value),
+        std::format!("{:width$.precision$}", value),
+    );
+    assert_eq!(format!("{:0$}", 8), std::format!("{:0$}", 8));
+    assert_eq!(format!("{:.*}", 3, value), std::format!("{:.*}", 3, value));
+    assert_eq!(
+        format!("{:2$.*}", 3, value, 10),
+        std::format!("{:2$.*}", 3, value, 10)
+    );
+}
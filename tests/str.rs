@@ -0,0 +1,38 @@
+#[macro_use]
+extern crate mono_fmt;
+
+const STRINGS: &[&str] = &[
+    "",
+    "plain",
+    "quotes \" and ' inside",
+    "back\\slash",
+    "new\nline\ttab\rreturn\0nul",
+    "\x01\x1b\x7f",
+    "unicode é ß 🦀 中文",
+    "\u{200b}zero width\u{feff}",
+    "\u{301}combining first, then a\u{301}",
+    "\u{10ffff}\u{e000}",
+];
+
+#[test]
+fn str_debug_matches_std() {
+    for s in STRINGS {
+        assert_eq!(format!("{:?}", s), std::format!("{:?}", s));
+        assert_eq!(format!("{:?}", s.to_string()), std::format!("{:?}", s));
+    }
+}
+
+#[test]
+fn char_debug_matches_std() {
+    for c in STRINGS.iter().flat_map(|s| s.chars()) {
+        assert_eq!(format!("{:?}", c), std::format!("{:?}", c));
+    }
+    assert_eq!(format!("{:?}", '\''), r"'\''");
+    assert_eq!(format!("{:?}", '"'), r#"'"'"#);
+}
+
+#[test]
+fn str_debug() {
+    assert_eq!(format!("{:?}", "a\"b'c\n"), r#""a\"b'c\n""#);
+    assert_eq!(format!("{:?}", "\u{7f}"), r#""\u{7f}""#);
+}
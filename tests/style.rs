@@ -0,0 +1,121 @@
+use mono_fmt::{Ansi, Attributes, Color, FmtOpts, Formatter, Style, Write};
+
+#[macro_use]
+extern crate mono_fmt;
+
+fn ansi(write: impl FnOnce(&mut Ansi<String>) -> mono_fmt::Result) -> String {
+    let mut out = Ansi::new(String::new());
+    write(&mut out).unwrap();
+    out.into_inner()
+}
+
+#[test]
+fn plain_text_drops_styles() {
+    assert_eq!(
+        format!("{:(fg=red,bold)}|{:>5(bg=blue)}|", "a", 1),
+        "a|    1|"
+    );
+    assert_eq!(format!("{:^7.2(italic, underline)?}", 1.5), " 1.50  ");
+}
+
+#[test]
+fn ansi_escapes() {
+    assert_eq!(
+        ansi(|out| write!(out, "{:(fg=red,bold)} {}", "error", 1)),
+        "\x1b[0;1;31merror\x1b[0m 1"
+    );
+    assert_eq!(
+        ansi(|out| write!(out, "{:>3(bg=bright_white, fg=bright_black)x}", 255)),
+        "\x1b[0;90;107m ff\x1b[0m"
+    );
+    // an empty style changes nothing
+    assert_eq!(ansi(|out| write!(out, "{:()}", 'c')), "c");
+}
+
+#[test]
+fn fill_is_styled() {
+    assert_eq!(
+        ansi(|out| write!(out, "{:*^7(bg=blue)}|", "ab")),
+        "\x1b[0;44m**ab***\x1b[0m|"
+    );
+    assert_eq!(
+        ansi(|out| write!(out, "{:-<4(underline)?}|", 7)),
+        "\x1b[0;4m7---\x1b[0m|"
+    );
+    assert_eq!(
+        ansi(|out| write!(out, "{:+06(fg=red)}", 5)),
+        "\x1b[0;31m+00005\x1b[0m"
+    );
+}
+
+struct Status(bool);
+
+impl mono_fmt::Display for Status {
+    fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> mono_fmt::Result {
+        f.write_str("[")?;
+        let (text, color) = match self.0 {
+            true => ("ok", Color::Green),
+            false => ("failed", Color::Red),
+        };
+        f.with_style(Style::PLAIN.fg(color), |f| f.write_str(text))?;
+        f.write_str("]")
+    }
+}
+
+#[test]
+fn styled_parts() {
+    assert_eq!(format!("{}", Status(false)), "[failed]");
+    assert_eq!(
+        ansi(|out| write!(out, "{}", Status(true))),
+        "[\x1b[0;32mok\x1b[0m]"
+    );
+    // the part is layered over the style of the value and goes back to it
+    assert_eq!(
+        ansi(|out| write!(out, "{:(bold, bg=blue)}", Status(false))),
+        "\x1b[0;1;44m[\x1b[0;1;31;44mfailed\x1b[0;1;44m]\x1b[0m"
+    );
+}
+
+#[derive(mono_fmt::Display)]
+#[display("{0:(underline)} at {1}")]
+struct Located(&'static str, Status);
+
+#[test]
+fn nested() {
+    // the inner value keeps the outer colors it doesn't set itself
+    assert_eq!(
+        ansi(|out| write!(out, "{:(fg=yellow)}", Located("x", Status(true)))),
+        "\x1b[0;33m\x1b[0;4;33mx\x1b[0;33m at [\x1b[0;32mok\x1b[0;33m]\x1b[0m"
+    );
+}
+
+#[test]
+fn runtime_styles() {
+    let style = Style::PLAIN.with(Attributes::BOLD.union(Attributes::ITALIC));
+    assert!(style.attributes.contains(Attributes::ITALIC));
+    assert_eq!(
+        Style::PLAIN.fg(Color::Red).patch(style).foreground,
+        Some(Color::Red)
+    );
+
+    let out = ansi(|out| {
+        Formatter::new(out).with_style(style.bg(Color::Cyan), |f| mono_fmt::Display::fmt(&7, f))
+    });
+    assert_eq!(out, "\x1b[0;1;3;46m7\x1b[0m");
+}
+
+struct Tagged;
+
+impl mono_fmt::Debug for Tagged {
+    fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> mono_fmt::Result {
+        f.with_style(Style::PLAIN.fg(Color::Cyan), |f| f.write_str("tag"))
+    }
+}
+
+#[test]
+fn pretty_debug() {
+    assert_eq!(
+        ansi(|out| write!(out, "{:#?}", [Tagged])),
+        "[\n    \x1b[0;36mtag\x1b[0m,\n]"
+    );
+}
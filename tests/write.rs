@@ -0,0 +1,167 @@
+#[macro_use]
+extern crate mono_fmt;
+
+use std::{error::Error as _, io};
+
+use mono_fmt::{ArrayString, Error, ErrorKind, FmtOpts, Formatter, IoWriter, Overflow, Std, Write};
+
+#[test]
+fn string() {
+    let mut string = String::new();
+    write!(string, "{} {:?}", 1, "a").unwrap();
+    writeln!(string, "!").unwrap();
+    writeln!(string).unwrap();
+    assert_eq!(string, "1 \"a\"!\n\n");
+}
+
+#[test]
+fn byte_slice() {
+    let mut buf = [0u8; 8];
+    let mut rest = &mut buf[..];
+    write!(rest, "{:>4}", 12).unwrap();
+    assert_eq!(rest.len(), 4);
+    assert_eq!(&buf[..4], b"  12");
+
+    let mut small = [0u8; 2];
+    let err = write!(&mut small[..], "{}", 123).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Capacity);
+    assert_eq!(io::Error::from(err).kind(), io::ErrorKind::WriteZero);
+
+    // a full cursor fails with `WriteZero`
+    let mut cursor = io::Cursor::new(vec![0; 2].into_boxed_slice());
+    let err = write!(cursor, "{}", 123).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Capacity);
+}
+
+#[test]
+fn array_string() {
+    let s = format_array!(16; "{:?} {:.1}", "a", 2.25).unwrap();
+    assert_eq!(s, "\"a\" 2.2");
+    assert_eq!((s.len(), s.capacity(), s.remaining()), (7, 16, 9));
+    assert_eq!(
+        format!("{:>9}|{:?}", s, s),
+        std::format!("{:>9}|{:?}", &*s, &*s)
+    );
+
+    // exactly full is fine, one byte more fails and keeps what was written before
+    let s: ArrayString<3> = format_array!("{}", 123).unwrap();
+    assert_eq!(s, "123");
+    let mut s = ArrayString::<3>::new();
+    write!(s, "ab").unwrap();
+    assert_eq!(
+        write!(s, "{}", "cd").unwrap_err().kind(),
+        ErrorKind::Capacity
+    );
+    assert_eq!(s, "ab");
+
+    let mut s = ArrayString::<5>::with_overflow(Overflow::Truncate);
+    write!(s, "{}{}", "ab", "🦀c").unwrap();
+    assert_eq!((s.as_str(), s.is_truncated()), ("ab", true));
+    // nothing is added after a cut, even if it fits
+    write!(s, "d").unwrap();
+    assert_eq!(s, "ab");
+    s.clear();
+    write!(s, "{:05}", 7).unwrap();
+    assert_eq!((s.as_str(), s.is_truncated()), ("00007", false));
+}
+
+#[test]
+fn file() {
+    use std::io::{Read, Seek, Write as _};
+
+    let path = std::env::temp_dir().join(format!("mono-fmt-write-{}", std::process::id()));
+    let mut file = std::fs::File::options()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&path)
+        .unwrap();
+    // `std::io::Write` being in scope must not make the macro ambiguous
+    writeln!(file, "{:x}", 255).unwrap();
+    file.flush().unwrap();
+
+    let mut contents = String::new();
+    file.rewind().unwrap();
+    file.read_to_string(&mut contents).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(contents, "ff\n");
+}
+
+struct Point(i32, i32);
+
+impl mono_fmt::Display for Point {
+    fn fmt<W: Write, O: FmtOpts>(&self, f: &mut Formatter<W, O>) -> mono_fmt::Result {
+        write!(f, "({}, {})", self.0, self.1)
+    }
+}
+
+#[test]
+fn formatter() {
+    assert_eq!(format!("{}", Point(1, -2)), "(1, -2)");
+}
+
+struct GiveUp;
+
+impl mono_fmt::Display for GiveUp {
+    fn fmt<W: Write, O: FmtOpts>(&self, _: &mut Formatter<W, O>) -> mono_fmt::Result {
+        Err(Error::default())
+    }
+}
+
+struct CoreGiveUp;
+
+impl std::fmt::Display for CoreGiveUp {
+    fn fmt(&self, _: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Err(std::fmt::Error)
+    }
+}
+
+#[test]
+fn error_kinds() {
+    let mut string = String::new();
+    let err = write!(string, "{}", GiveUp).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::User);
+    assert_eq!(
+        err.to_string(),
+        "an error occurred when formatting an argument"
+    );
+    assert_eq!(
+        write!(string, "{:>5}", Std(&CoreGiveUp))
+            .unwrap_err()
+            .kind(),
+        ErrorKind::User
+    );
+    assert_eq!(Error::from(std::fmt::Error).kind(), ErrorKind::User);
+    let _: std::fmt::Error = Error::new(ErrorKind::Capacity).into();
+}
+
+#[test]
+fn io_errors() {
+    let path = std::env::temp_dir().join(format!("mono-fmt-error-{}", std::process::id()));
+    std::fs::write(&path, "").unwrap();
+    let mut read_only = std::fs::File::open(&path).unwrap();
+
+    let err = write!(read_only, "{}", 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Io);
+    assert_eq!(err.to_string(), "writing the formatted output failed");
+    assert!(err.source().is_none());
+    assert_eq!(io::Error::from(err).kind(), io::ErrorKind::Other);
+
+    // the writer keeps the cause, also when it goes through `core::fmt` for the `Std` bridge,
+    // padded or not
+    let mut out = IoWriter::new(read_only);
+    let err = write!(out, "{}", 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Io);
+    let raw = out.take_error().unwrap().raw_os_error();
+    assert!(raw.is_some());
+    assert!(out.take_error().is_none());
+    let err = write!(out, "{}", Std(&1.5)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Io);
+    assert_eq!(out.take_error().unwrap().raw_os_error(), raw);
+    let err = write!(out, "{:*^9}", Std(&1.5)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Io);
+    assert_eq!(out.take_error().unwrap().raw_os_error(), raw);
+    drop(out.into_inner());
+    std::fs::remove_file(&path).unwrap();
+}